@@ -0,0 +1,211 @@
+use std::{
+    borrow::Borrow,
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+use crate::{DisjointHashSet, PointerId};
+
+/// A value maintained for every disjoint set and combined as sets are joined.
+///
+/// Implementations describe the value of a singleton set and how the values
+/// of two sets are merged. `combine` should be associative and commutative as
+/// the order in which sets are joined is an implementation detail.
+pub trait Aggregate<K> {
+    type Value;
+
+    /// The value of a newly inserted set containing only `key`.
+    fn singleton(&self, key: &K) -> Self::Value;
+
+    /// Combines the values of two sets being joined into one.
+    fn combine(&self, a: Self::Value, b: Self::Value) -> Self::Value;
+}
+
+/// Counts the members of each set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Count;
+
+impl<K> Aggregate<K> for Count {
+    type Value = usize;
+
+    fn singleton(&self, _: &K) -> usize {
+        1
+    }
+
+    fn combine(&self, a: usize, b: usize) -> usize {
+        a + b
+    }
+}
+
+/// An [`Aggregate`] built from a pair of closures.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{AggregatedDisjointHashSet, Fold};
+///
+/// // Track the maximum key of each set.
+/// let mut djhs = AggregatedDisjointHashSet::new(Fold::new(|k: &u32| *k, u32::max));
+/// djhs.link(1, 7);
+/// djhs.link(3, 4);
+/// assert_eq!(djhs.aggregate(&1), Some(&7));
+/// assert_eq!(djhs.aggregate(&3), Some(&4));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Fold<F, G> {
+    singleton: F,
+    combine: G,
+}
+
+impl<F, G> Fold<F, G> {
+    /// Creates an aggregate from a closure producing the value of a singleton
+    /// set and a closure combining the values of two sets.
+    pub fn new(singleton: F, combine: G) -> Self {
+        Self { singleton, combine }
+    }
+}
+
+impl<K, V, F: Fn(&K) -> V, G: Fn(V, V) -> V> Aggregate<K> for Fold<F, G> {
+    type Value = V;
+
+    fn singleton(&self, key: &K) -> V {
+        (self.singleton)(key)
+    }
+
+    fn combine(&self, a: V, b: V) -> V {
+        (self.combine)(a, b)
+    }
+}
+
+/// A `DisjointHashSet` maintaining an [`Aggregate`] for every set.
+///
+/// Aggregates are combined incrementally on every effective `link`, so
+/// querying the aggregate of a set costs no more than finding its root.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{AggregatedDisjointHashSet, Count, Fold};
+///
+/// let mut sizes = AggregatedDisjointHashSet::new(Count);
+/// sizes.link("a", "b");
+/// sizes.link("b", "c");
+/// assert_eq!(sizes.aggregate(&"a"), Some(&3));
+///
+/// // Values that aren't derived from the key can be supplied on insertion.
+/// let mut totals = AggregatedDisjointHashSet::new(Fold::new(|_: &&str| 0, |a, b| a + b));
+/// totals.insert_with("alice", 10);
+/// totals.insert_with("bob", 5);
+/// totals.link("alice", "bob");
+/// assert_eq!(totals.aggregate(&"bob"), Some(&15));
+/// ```
+#[derive(Debug, Clone)]
+pub struct AggregatedDisjointHashSet<K, A: Aggregate<K>, S = RandomState> {
+    set: DisjointHashSet<K, S>,
+    values: Vec<Option<A::Value>>,
+    aggregate: A,
+}
+
+impl<K: Eq + Hash, A: Aggregate<K>> AggregatedDisjointHashSet<K, A, RandomState> {
+    /// Creates an empty `AggregatedDisjointHashSet` maintaining `aggregate`.
+    pub fn new(aggregate: A) -> Self {
+        Self { set: DisjointHashSet::new(), values: Vec::new(), aggregate }
+    }
+}
+
+impl<K: Eq + Hash, A: Aggregate<K>, S: BuildHasher> AggregatedDisjointHashSet<K, A, S> {
+    /// Creates an empty `AggregatedDisjointHashSet` maintaining `aggregate`,
+    /// using `hash_builder` to hash the keys.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::hash::RandomState;
+    /// use disjoint_hash_set::{AggregatedDisjointHashSet, Count};
+    /// let s = RandomState::new();
+    /// let mut djhs = AggregatedDisjointHashSet::with_hasher(Count, s);
+    /// djhs.insert("a");
+    /// ```
+    pub fn with_hasher(aggregate: A, hash_builder: S) -> Self {
+        Self { set: DisjointHashSet::with_hasher(hash_builder), values: Vec::new(), aggregate }
+    }
+
+    /// Check if the value has already been inserted.
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.set.contains(val)
+    }
+
+    /// Insert the value as a new disjoint set with a single member, its
+    /// aggregate given by [`Aggregate::singleton`]. Returns true if the value
+    /// was not already present.
+    pub fn insert(&mut self, val: K) -> bool {
+        (!self.contains(&val)).then(|| self.insert_unchecked(val)).is_some()
+    }
+
+    /// Insert the value as a new disjoint set with a single member, its
+    /// aggregate given by `value`. Returns true if the value was not already
+    /// present, otherwise the existing aggregate is left untouched.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{AggregatedDisjointHashSet, Count};
+    ///
+    /// let mut djhs = AggregatedDisjointHashSet::new(Count);
+    /// assert!(djhs.insert_with("a", 5));
+    /// assert!(!djhs.insert_with("a", 1));
+    /// assert_eq!(djhs.aggregate(&"a"), Some(&5));
+    /// ```
+    pub fn insert_with(&mut self, val: K, value: A::Value) -> bool {
+        if self.contains(&val) {
+            return false;
+        }
+
+        let id = self.set.insert_unchecked(val);
+        self.values.push(Some(value));
+        debug_assert_eq!(id.0 + 1, self.values.len());
+        true
+    }
+
+    /// Checks if the two keys are members of the same set.
+    /// This will not implicitly add values that were not already present.
+    pub fn is_linked<T: Borrow<K>>(&mut self, val1: T, val2: T) -> bool {
+        self.set.is_linked(val1, val2)
+    }
+
+    /// Link the respective sets of the two provided values, combining their
+    /// aggregates. This will insert non-existent values in the process.
+    pub fn link(&mut self, val1: K, val2: K) {
+        let ids = (self.id_or_insert(val1), self.id_or_insert(val2));
+
+        if let Some((root, child)) = self.set.union(ids.0, ids.1) {
+            let a = self.values[root.0].take().expect("roots hold an aggregate");
+            let b = self.values[child.0].take().expect("roots hold an aggregate");
+            self.values[root.0] = Some(self.aggregate.combine(a, b));
+        }
+    }
+
+    /// Returns the aggregate of the set containing the value, or `None` if the
+    /// value is not present.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{AggregatedDisjointHashSet, Count};
+    ///
+    /// let mut djhs = AggregatedDisjointHashSet::new(Count);
+    /// djhs.link("a", "b");
+    /// assert_eq!(djhs.aggregate(&"b"), Some(&2));
+    /// assert_eq!(djhs.aggregate(&"c"), None);
+    /// ```
+    pub fn aggregate<T: Borrow<K>>(&mut self, val: T) -> Option<&A::Value> {
+        let root = self.set.id(val.borrow()).map(|id| self.set.find(id))?;
+        self.values[root.0].as_ref()
+    }
+
+    /// Consumes the structure, returning the underlying `DisjointHashSet`.
+    pub fn into_inner(self) -> DisjointHashSet<K, S> {
+        self.set
+    }
+
+    fn id_or_insert(&mut self, value: K) -> PointerId {
+        self.set.id(&value).unwrap_or_else(|| self.insert_unchecked(value))
+    }
+
+    fn insert_unchecked(&mut self, value: K) -> PointerId {
+        self.values.push(Some(self.aggregate.singleton(&value)));
+        self.set.insert_unchecked(value)
+    }
+}
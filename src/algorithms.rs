@@ -0,0 +1,1013 @@
+//! Graph and lattice algorithms built on [`DisjointHashSet`] and its dense
+//! counterpart.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    io::{self, Read, Write},
+    iter::Sum,
+    num::NonZeroUsize,
+    panic,
+    path::Path,
+    thread,
+};
+
+use crate::{
+    Connectivity, DisjointHashSet, PointerId, dense::DenseDisjointSet, external,
+    generators::shuffle,
+};
+
+/// Groups the keys of the edges into connected components, in the order
+/// their first keys appear. A key linked to itself forms a component of its
+/// own.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::connected_components;
+/// use std::collections::HashSet;
+///
+/// let components = connected_components(vec![("a", "b"), ("c", "c"), ("b", "d")]);
+/// assert_eq!(components, vec![HashSet::from(["a", "b", "d"]), HashSet::from(["c"])]);
+/// ```
+pub fn connected_components<K, I>(edges: I) -> Vec<HashSet<K>>
+where
+    K: Eq + Hash,
+    I: IntoIterator<Item = (K, K)>,
+{
+    DisjointHashSet::from_edges_bulk(edges).sets().collect()
+}
+
+/// A minimum spanning forest, as returned by [`minimum_spanning_forest`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanningForest<K, W> {
+    /// The edges of the forest, lightest first.
+    pub edges: Vec<(K, K, W)>,
+    /// The sum of the weights of the edges.
+    pub weight: W,
+}
+
+/// Computes a minimum spanning forest of the weighted edges with Kruskal's
+/// algorithm: edges are taken lightest first, keeping those that join two
+/// trees. Of edges with equal weights, earlier ones are preferred.
+///
+/// # Panics
+/// Panics if two weights can't be compared, such as a `NaN`.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::algorithms::minimum_spanning_forest;
+///
+/// let edges = vec![("a", "b", 4), ("b", "c", 1), ("a", "c", 2), ("c", "d", 7), ("x", "y", 3)];
+/// let forest = minimum_spanning_forest(edges);
+///
+/// assert_eq!(forest.edges, vec![("b", "c", 1), ("a", "c", 2), ("x", "y", 3), ("c", "d", 7)]);
+/// assert_eq!(forest.weight, 13);
+/// ```
+pub fn minimum_spanning_forest<K, W, I>(edges: I) -> SpanningForest<K, W>
+where
+    K: Eq + Hash,
+    W: PartialOrd + Clone + Sum,
+    I: IntoIterator<Item = (K, K, W)>,
+{
+    let mut edges: Vec<(K, K, W)> = edges.into_iter().collect();
+    edges.sort_by(|a, b| a.2.partial_cmp(&b.2).expect("edge weights must be comparable"));
+
+    let edges = spanning_edges(edges, |(key1, key2, _)| (key1, key2));
+    let weight = edges.iter().map(|(_, _, weight)| weight.clone()).sum();
+    SpanningForest { edges, weight }
+}
+
+/// Computes the same minimum spanning forest as [`minimum_spanning_forest`]
+/// with Borůvka's algorithm, searching for edges on all available threads.
+///
+/// Every phase finds the lightest edge leaving each tree and joins the trees
+/// along those edges, at least halving their number. The edges are split
+/// between threads for the search, while the unions of each phase are applied
+/// on the calling thread. Ties between equal weights are broken in favour of
+/// earlier edges, so the result does not depend on the number of threads.
+///
+/// # Panics
+/// Panics if two weights can't be compared, such as a `NaN`.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::algorithms::{
+///     minimum_spanning_forest, parallel_minimum_spanning_forest,
+/// };
+///
+/// let edges: Vec<(u32, u32, u64)> =
+///     (0..1_000).map(|i| (i, (i * 7 + 3) % 1_000, (i as u64 * 31) % 97)).collect();
+/// assert_eq!(parallel_minimum_spanning_forest(edges.clone()), minimum_spanning_forest(edges));
+/// ```
+pub fn parallel_minimum_spanning_forest<K, W, I>(edges: I) -> SpanningForest<K, W>
+where
+    K: Eq + Hash,
+    W: PartialOrd + Clone + Sum + Sync,
+    I: IntoIterator<Item = (K, K, W)>,
+{
+    let edges: Vec<(K, K, W)> = edges.into_iter().collect();
+    let mut djhs = DisjointHashSet::with_capacity(edges.len());
+    let ends: Vec<(PointerId, PointerId)> = edges
+        .iter()
+        .map(|(key1, key2, _)| (djhs.id_or_insert(key1), djhs.id_or_insert(key2)))
+        .collect();
+
+    let weights: Vec<&W> = edges.iter().map(|(_, _, weight)| weight).collect();
+    let order = |i: &usize, j: &usize| {
+        weights[*i]
+            .partial_cmp(weights[*j])
+            .expect("edge weights must be comparable")
+            .then(i.cmp(j))
+    };
+    let lighter = |i: usize, j: usize| order(&i, &j).is_lt();
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+
+    let (mut candidates, mut kept): (Vec<usize>, _) = ((0..edges.len()).collect(), Vec::new());
+    loop {
+        let trees: Vec<PointerId> =
+            (0..djhs.forest.len()).map(|id| djhs.find(PointerId(id))).collect();
+        candidates.retain(|&edge| trees[ends[edge].0.0] != trees[ends[edge].1.0]);
+        if candidates.is_empty() {
+            break;
+        }
+
+        let (trees, ends, lighter) = (&trees, &ends, &lighter);
+        let cheapest = |edges: &mut dyn Iterator<Item = (PointerId, usize)>| {
+            let mut cheapest = HashMap::new();
+            for (tree, edge) in edges {
+                let best = cheapest.entry(tree).or_insert(edge);
+                if lighter(edge, *best) {
+                    *best = edge;
+                }
+            }
+            cheapest
+        };
+
+        let chunk = candidates.len().div_ceil(threads);
+        let cheapest = thread::scope(|scope| {
+            let searches: Vec<_> = candidates
+                .chunks(chunk)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        cheapest(&mut chunk.iter().flat_map(|&edge| {
+                            [(trees[ends[edge].0.0], edge), (trees[ends[edge].1.0], edge)]
+                        }))
+                    })
+                })
+                .collect();
+
+            let found: Vec<HashMap<PointerId, usize>> = searches
+                .into_iter()
+                .map(|search| search.join().unwrap_or_else(|panic| panic::resume_unwind(panic)))
+                .collect();
+            cheapest(&mut found.into_iter().flatten())
+        });
+
+        for edge in cheapest.into_values() {
+            if djhs.union(ends[edge].0, ends[edge].1).is_some() {
+                kept.push(edge);
+            }
+        }
+    }
+    kept.sort_by(order);
+
+    let mut edges: Vec<Option<(K, K, W)>> = edges.into_iter().map(Some).collect();
+    let edges: Vec<(K, K, W)> = kept.into_iter().filter_map(|edge| edges[edge].take()).collect();
+    let weight = edges.iter().map(|(_, _, weight)| weight.clone()).sum();
+    SpanningForest { edges, weight }
+}
+
+/// Computes a random spanning forest of the edges, as in randomized Kruskal
+/// maze generation: the edges are shuffled and those joining two trees are
+/// kept, in shuffled order.
+///
+/// `random_below(n)` must return a uniformly random number below `n`, e.g.
+/// from the caller's own random number generator.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::algorithms::random_spanning_forest;
+///
+/// // Carve a maze through a 4x4 grid, with a tiny xorshift generator.
+/// let mut state = 0x2545_f491_u64;
+/// let random_below = |n: usize| {
+///     state ^= state << 13;
+///     state ^= state >> 7;
+///     state ^= state << 17;
+///     (state % n as u64) as usize
+/// };
+///
+/// let walls = (0..4).flat_map(|y| {
+///     (0..4).flat_map(move |x| {
+///         let right = (x < 3).then_some(((x, y), (x + 1, y)));
+///         let down = (y < 3).then_some(((x, y), (x, y + 1)));
+///         right.into_iter().chain(down)
+///     })
+/// });
+/// let passages = random_spanning_forest(walls, random_below);
+/// assert_eq!(passages.len(), 15);
+/// ```
+pub fn random_spanning_forest<K, I, R>(edges: I, mut random_below: R) -> Vec<(K, K)>
+where
+    K: Eq + Hash,
+    I: IntoIterator<Item = (K, K)>,
+    R: FnMut(usize) -> usize,
+{
+    let mut edges: Vec<(K, K)> = edges.into_iter().collect();
+    shuffle(&mut edges, &mut random_below);
+    spanning_edges(edges, |(key1, key2)| (key1, key2))
+}
+
+/// Keeps the edges, in order, that join two previously disjoint trees.
+fn spanning_edges<E, K: Eq + Hash>(edges: Vec<E>, keys: impl Fn(&E) -> (&K, &K)) -> Vec<E> {
+    let mut djhs = DisjointHashSet::with_capacity(edges.len());
+    let merging: Vec<bool> = edges
+        .iter()
+        .map(|edge| {
+            let (key1, key2) = keys(edge);
+            let ids = (djhs.id_or_insert(key1), djhs.id_or_insert(key2));
+            djhs.union(ids.0, ids.1).is_some()
+        })
+        .collect();
+
+    edges.into_iter().zip(merging).filter_map(|(edge, merged)| merged.then_some(edge)).collect()
+}
+
+/// Answers lowest common ancestor queries on the tree of `edges` rooted at
+/// `root`, offline with Tarjan's algorithm, in `O((n + q) α(n))`. A query is
+/// answered with `None` if either key is not part of the root's tree.
+///
+/// The edges are undirected and must form a forest.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::algorithms::lowest_common_ancestors;
+///
+/// //       a
+/// //      / \
+/// //     b   c
+/// //    / \
+/// //   d   e
+/// let edges = [("a", "b"), ("a", "c"), ("d", "b"), ("b", "e")];
+/// let queries = [("d", "e"), ("e", "c"), ("b", "d"), ("d", "z")];
+///
+/// let answers = lowest_common_ancestors("a", edges, queries);
+/// assert_eq!(answers, vec![Some("b"), Some("a"), Some("b"), None]);
+/// ```
+pub fn lowest_common_ancestors<K, E, Q>(root: K, edges: E, queries: Q) -> Vec<Option<K>>
+where
+    K: Eq + Hash + Clone,
+    E: IntoIterator<Item = (K, K)>,
+    Q: IntoIterator<Item = (K, K)>,
+{
+    let mut djhs = DisjointHashSet::new();
+    let root = djhs.id_or_insert(root);
+
+    let mut children: Vec<Vec<PointerId>> = vec![Vec::new()];
+    for (key1, key2) in edges {
+        let ids = (djhs.id_or_insert(key1), djhs.id_or_insert(key2));
+        children.resize(djhs.forest.len(), Vec::new());
+        children[ids.0.0].push(ids.1);
+        children[ids.1.0].push(ids.0);
+    }
+
+    let mut asked: Vec<Vec<(PointerId, usize)>> = vec![Vec::new(); djhs.forest.len()];
+    let mut answers = Vec::new();
+    for (index, (key1, key2)) in queries.into_iter().enumerate() {
+        answers.push(None);
+        if let (Some(id1), Some(id2)) = (djhs.id(&key1), djhs.id(&key2)) {
+            asked[id1.0].push((id2, index));
+            asked[id2.0].push((id1, index));
+        }
+    }
+
+    let mut ancestors: Vec<PointerId> = (0..djhs.forest.len()).map(PointerId).collect();
+    let (mut visited, mut finished) =
+        (vec![false; djhs.forest.len()], vec![false; djhs.forest.len()]);
+    let mut stack = vec![(root, 0)];
+    visited[root.0] = true;
+
+    while let Some((id, next)) = stack.last_mut() {
+        let id = *id;
+        if let Some(&child) = children[id.0].get(*next) {
+            *next += 1;
+            if !visited[child.0] {
+                visited[child.0] = true;
+                stack.push((child, 0));
+            }
+            continue;
+        }
+
+        stack.pop();
+        finished[id.0] = true;
+        for &(other, index) in &asked[id.0] {
+            if finished[other.0] {
+                let ancestor = ancestors[djhs.find(other).0];
+                answers[index] = Some(djhs.key(ancestor).clone());
+            }
+        }
+
+        if let Some(&(parent, _)) = stack.last() {
+            djhs.union(parent, id);
+            let root = djhs.find(parent);
+            ancestors[root.0] = parent;
+        }
+    }
+    answers
+}
+
+/// The merge tree of single-linkage clustering, as returned by
+/// [`single_linkage`].
+///
+/// Clusters are numbered like the rows of a linkage matrix: the leaves are
+/// `0..n`, and the cluster formed by the `i`th merge is `n + i`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dendrogram<K, W> {
+    leaves: Vec<K>,
+    nodes: Vec<DendrogramNode<W>>,
+}
+
+/// A merge of two clusters in a [`Dendrogram`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DendrogramNode<W> {
+    /// The clusters that were merged.
+    pub children: (usize, usize),
+    /// The weight of the edge that merged them.
+    pub weight: W,
+    /// The number of leaves of the merged cluster.
+    pub size: usize,
+}
+
+/// Clusters the keys of the weighted edges by single linkage, recording which
+/// clusters merged at which weight. Leaves are numbered in the order their
+/// keys first appear.
+///
+/// Edges are taken lightest first, with ties going to earlier edges, and every
+/// edge joining two clusters adds a node to the dendrogram.
+///
+/// # Panics
+/// Panics if two weights can't be compared, such as a `NaN`.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::algorithms::single_linkage;
+///
+/// let edges = vec![("a", "b", 0.5), ("c", "d", 1.5), ("b", "c", 3.0), ("d", "e", 0.25)];
+/// let dendrogram = single_linkage(edges);
+///
+/// assert_eq!(dendrogram.leaves(), &["a", "b", "c", "d", "e"]);
+/// assert_eq!(dendrogram.nodes().len(), 4);
+/// assert_eq!(dendrogram.cut(&1.0), vec![vec![&"a", &"b"], vec![&"c"], vec![&"d", &"e"]]);
+/// assert_eq!(dendrogram.cut(&2.0).len(), 2);
+/// ```
+pub fn single_linkage<K, W, I>(edges: I) -> Dendrogram<K, W>
+where
+    K: Eq + Hash,
+    W: PartialOrd,
+    I: IntoIterator<Item = (K, K, W)>,
+{
+    let edges = edges.into_iter();
+    let mut djhs = DisjointHashSet::with_capacity(edges.size_hint().0);
+    let mut edges: Vec<(PointerId, PointerId, W)> = edges
+        .map(|(key1, key2, weight)| (djhs.id_or_insert(key1), djhs.id_or_insert(key2), weight))
+        .collect();
+    edges.sort_by(|a, b| a.2.partial_cmp(&b.2).expect("edge weights must be comparable"));
+
+    let leaves = djhs.forest.len();
+    let (mut clusters, mut nodes): (Vec<usize>, Vec<_>) = ((0..leaves).collect(), Vec::new());
+    for (id1, id2, weight) in edges {
+        let children = (clusters[djhs.find(id1).0], clusters[djhs.find(id2).0]);
+        if let Some((root, _)) = djhs.union(id1, id2) {
+            nodes.push(DendrogramNode { children, weight, size: djhs.get(root).size });
+            clusters[root.0] = leaves + nodes.len() - 1;
+        }
+    }
+
+    Dendrogram { leaves: djhs.keys, nodes }
+}
+
+impl<K, W: PartialOrd> Dendrogram<K, W> {
+    /// The keys, indexed by leaf cluster.
+    pub fn leaves(&self) -> &[K] {
+        &self.leaves
+    }
+
+    /// The merges, lightest first, indexed by cluster minus the number of
+    /// leaves.
+    pub fn nodes(&self) -> &[DendrogramNode<W>] {
+        &self.nodes
+    }
+
+    /// Cuts the dendrogram at the threshold, returning the clusters formed by
+    /// merges of weight up to and including it. Clusters, and their members,
+    /// are ordered by leaf.
+    pub fn cut(&self, threshold: &W) -> Vec<Vec<&K>> {
+        let mut djhs = DisjointHashSet::with_capacity(self.leaves.len());
+        self.leaves.iter().enumerate().for_each(|(leaf, _)| {
+            djhs.insert_unchecked(leaf);
+        });
+
+        // A leaf of every cluster, to stand in for it.
+        let mut leaves: Vec<usize> = (0..self.leaves.len()).collect();
+        for node in self.nodes.iter().take_while(|node| node.weight <= *threshold) {
+            let (leaf1, leaf2) = (leaves[node.children.0], leaves[node.children.1]);
+            djhs.union(PointerId(leaf1), PointerId(leaf2));
+            leaves.push(leaf1);
+        }
+
+        djhs.ordered_sets()
+            .map(|set| set.into_iter().map(|leaf| &self.leaves[leaf]).collect())
+            .collect()
+    }
+}
+
+/// The number of components and the size of the largest one, as a function of
+/// a threshold on edge weights, as returned by [`connectivity_profile`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectivityProfile<W> {
+    /// The number of keys, each a component of its own below every threshold.
+    pub keys: usize,
+    /// One step for every weight at which components merged, ascending.
+    pub steps: Vec<ThresholdStep<W>>,
+}
+
+/// The components formed by the edges of weight up to and including
+/// `threshold`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ThresholdStep<W> {
+    /// The weight of the edges that formed this step.
+    pub threshold: W,
+    /// The number of components.
+    pub components: usize,
+    /// The number of keys in the largest component.
+    pub largest: usize,
+}
+
+/// Computes how the components of the weighted edges evolve as edges are
+/// admitted in ascending order of weight, in a single sorted pass.
+///
+/// # Panics
+/// Panics if two weights can't be compared, such as a `NaN`.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::algorithms::connectivity_profile;
+///
+/// let edges = vec![("a", "b", 2), ("c", "d", 1), ("b", "c", 5), ("a", "d", 5), ("e", "e", 0)];
+/// let profile = connectivity_profile(edges);
+///
+/// assert_eq!(profile.keys, 5);
+/// assert_eq!(profile.steps.len(), 3);
+/// assert_eq!(profile.at(&0), (5, 1));
+/// assert_eq!(profile.at(&3), (3, 2));
+/// assert_eq!(profile.at(&9), (2, 4));
+/// ```
+pub fn connectivity_profile<K, W, I>(edges: I) -> ConnectivityProfile<W>
+where
+    K: Eq + Hash,
+    W: PartialOrd,
+    I: IntoIterator<Item = (K, K, W)>,
+{
+    let edges = edges.into_iter();
+    let mut djhs = DisjointHashSet::with_capacity(edges.size_hint().0);
+    let mut edges: Vec<(PointerId, PointerId, W)> = edges
+        .map(|(key1, key2, weight)| (djhs.id_or_insert(key1), djhs.id_or_insert(key2), weight))
+        .collect();
+    edges.sort_by(|a, b| a.2.partial_cmp(&b.2).expect("edge weights must be comparable"));
+
+    let keys = djhs.forest.len();
+    let (mut components, mut largest, mut merged) = (keys, keys.min(1), false);
+    let mut steps = Vec::new();
+
+    let mut edges = edges.into_iter().peekable();
+    while let Some((id1, id2, threshold)) = edges.next() {
+        if let Some((root, _)) = djhs.union(id1, id2) {
+            components -= 1;
+            largest = largest.max(djhs.get(root).size);
+            merged = true;
+        }
+
+        let last = edges.peek().is_none_or(|(_, _, next)| *next != threshold);
+        if last && merged {
+            steps.push(ThresholdStep { threshold, components, largest });
+            merged = false;
+        }
+    }
+
+    ConnectivityProfile { keys, steps }
+}
+
+impl<W: PartialOrd> ConnectivityProfile<W> {
+    /// Returns the number of components and the size of the largest one
+    /// formed by the edges of weight up to and including the threshold.
+    pub fn at(&self, threshold: &W) -> (usize, usize) {
+        match self.steps.partition_point(|step| step.threshold <= *threshold) {
+            0 => (self.keys, self.keys.min(1)),
+            steps => (self.steps[steps - 1].components, self.steps[steps - 1].largest),
+        }
+    }
+}
+
+/// The clusters of occupied sites of a lattice, as returned by
+/// [`hoshen_kopelman`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClusterLabels {
+    /// The cluster of every site in row-major order, or `None` for empty
+    /// sites. Clusters are numbered in the order of their first sites.
+    pub labels: Vec<Option<usize>>,
+    /// The number of sites of every cluster.
+    pub sizes: Vec<usize>,
+}
+
+/// Labels the clusters of nearest-neighbour occupied sites of a lattice with
+/// the Hoshen–Kopelman algorithm, scanning the sites once and joining every
+/// occupied site with its occupied predecessors along each axis.
+///
+/// The `occupied` sites are laid out in row-major order over the `shape`,
+/// with the last axis varying fastest, so that a 2D lattice has the shape
+/// `[rows, columns]` and a 3D lattice `[layers, rows, columns]`. Along every
+/// axis flagged in `periodic`, the last sites neighbour the first ones.
+///
+/// # Panics
+/// Panics if `occupied` does not hold a site for every point of the shape, or
+/// if `periodic` does not hold a flag for every axis.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::algorithms::hoshen_kopelman;
+///
+/// #[rustfmt::skip]
+/// let occupied = [
+///     true,  false, false, true,
+///     false, false, false, false,
+///     true,  true,  false, false,
+/// ];
+///
+/// let open = hoshen_kopelman(&occupied, &[3, 4], &[false, false]);
+/// assert_eq!(open.sizes, vec![1, 1, 2]);
+/// assert_eq!(open.labels[8], Some(2));
+/// assert_eq!(open.labels[9], Some(2));
+///
+/// let cylinder = hoshen_kopelman(&occupied, &[3, 4], &[false, true]);
+/// assert_eq!(cylinder.sizes, vec![2, 2]);
+///
+/// let torus = hoshen_kopelman(&occupied, &[3, 4], &[true, true]);
+/// assert_eq!(torus.sizes, vec![4]);
+/// assert_eq!(torus.labels[1], None);
+/// ```
+pub fn hoshen_kopelman(occupied: &[bool], shape: &[usize], periodic: &[bool]) -> ClusterLabels {
+    assert_eq!(occupied.len(), shape.iter().product(), "the lattice must hold every site");
+    assert_eq!(periodic.len(), shape.len(), "periodicity must be flagged for every axis");
+
+    let mut strides = vec![1; shape.len()];
+    for axis in (1..shape.len()).rev() {
+        strides[axis - 1] = strides[axis] * shape[axis];
+    }
+
+    let mut sites = DenseDisjointSet::new(occupied.len());
+    for site in (0..occupied.len()).filter(|&site| occupied[site]) {
+        for (axis, (&extent, &stride)) in shape.iter().zip(&strides).enumerate() {
+            let neighbour = match (site / stride) % extent {
+                0 if periodic[axis] => site + (extent - 1) * stride,
+                0 => continue,
+                _ => site - stride,
+            };
+            if occupied[neighbour] {
+                sites.union(site, neighbour);
+            }
+        }
+    }
+
+    let roots = sites.roots();
+    let (mut clusters, mut sizes) = (vec![None; occupied.len()], Vec::new());
+    let labels = (0..occupied.len())
+        .map(|site| {
+            occupied[site].then(|| {
+                let label = *clusters[roots[site]].get_or_insert_with(|| {
+                    sizes.push(0);
+                    sizes.len() - 1
+                });
+                sizes[label] += 1;
+                label
+            })
+        })
+        .collect();
+
+    ClusterLabels { labels, sizes }
+}
+
+/// The components of an image, as returned by [`label_image`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImageLabels {
+    /// The component of every pixel in row-major order, or `None` for
+    /// background pixels. Components are numbered in the order of their first
+    /// pixels.
+    pub labels: Vec<Option<usize>>,
+    /// The statistics of every component.
+    pub components: Vec<ComponentStats>,
+}
+
+/// The extent of a component of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ComponentStats {
+    /// The number of pixels.
+    pub area: usize,
+    /// The smallest `(x, y)` coordinates of any pixel.
+    pub min: (usize, usize),
+    /// The largest `(x, y)` coordinates of any pixel.
+    pub max: (usize, usize),
+}
+
+/// Labels the connected components of an image buffer of `width` pixels per
+/// row, in row-major order. Neighbouring pixels of equal value join the same
+/// component, except for those equal to the `background`.
+///
+/// A binary image is labelled with a `background` of `false` or `0`, while
+/// pixels of a label image only join neighbours of the same label.
+///
+/// # Panics
+/// Panics if the buffer does not hold a whole number of rows.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{Connectivity, algorithms::label_image};
+///
+/// #[rustfmt::skip]
+/// let pixels = [
+///     1, 1, 0, 2,
+///     0, 1, 0, 2,
+///     1, 0, 0, 2,
+/// ];
+///
+/// let four = label_image(&pixels, 4, Connectivity::Four, &0);
+/// assert_eq!(four.components.len(), 3);
+/// assert_eq!(four.labels[..4], [Some(0), Some(0), None, Some(1)]);
+/// assert_eq!(four.components[1].area, 3);
+/// assert_eq!((four.components[1].min, four.components[1].max), ((3, 0), (3, 2)));
+///
+/// let eight = label_image(&pixels, 4, Connectivity::Eight, &0);
+/// assert_eq!(eight.components.len(), 2);
+/// assert_eq!(eight.labels[8], Some(0));
+/// ```
+pub fn label_image<T: PartialEq>(
+    pixels: &[T],
+    width: usize,
+    connectivity: Connectivity,
+    background: &T,
+) -> ImageLabels {
+    assert!(pixels.len().is_multiple_of(width), "the image must hold a whole number of rows");
+
+    let mut sets = DenseDisjointSet::new(pixels.len());
+    for (pixel, value) in pixels.iter().enumerate().filter(|(_, value)| *value != background) {
+        let (x, y) = (pixel % width, pixel / width);
+        for (nx, ny) in connectivity.neighbors((x, y)) {
+            let neighbor = ny * width + nx;
+            if (ny, nx) < (y, x) && nx < width && pixels[neighbor] == *value {
+                sets.union(pixel, neighbor);
+            }
+        }
+    }
+
+    let roots = sets.roots();
+    let (mut slots, mut components) = (vec![None; pixels.len()], Vec::<ComponentStats>::new());
+    let labels = (0..pixels.len())
+        .map(|pixel| {
+            if pixels[pixel] == *background {
+                return None;
+            }
+
+            let (x, y) = (pixel % width, pixel / width);
+            let label = *slots[roots[pixel]].get_or_insert_with(|| {
+                components.push(ComponentStats { area: 0, min: (x, y), max: (x, y) });
+                components.len() - 1
+            });
+            let stats = &mut components[label];
+            stats.area += 1;
+            stats.min = (stats.min.0.min(x), stats.min.1.min(y));
+            stats.max = (stats.max.0.max(x), stats.max.1.max(y));
+            Some(label)
+        })
+        .collect();
+
+    ImageLabels { labels, components }
+}
+
+/// Clusters the points, linking every two points at most `radius` apart by
+/// Euclidean distance, and returns the indices of the points of every
+/// cluster. Clusters, and their members, are ordered by index.
+///
+/// Neighbours are searched by hashing the points into a grid of cells the size
+/// of the radius, so that only the points of adjacent cells are compared.
+///
+/// # Panics
+/// Panics if the radius is not positive and finite.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::algorithms::radius_clusters;
+///
+/// let points = [[0.0, 0.0], [0.0, 0.9], [5.0, 5.0], [0.7, 1.5], [5.5, 5.0]];
+/// assert_eq!(radius_clusters(&points, 1.0), vec![vec![0, 1, 3], vec![2, 4]]);
+/// assert_eq!(radius_clusters(&points, 0.5).len(), 4);
+/// ```
+pub fn radius_clusters<const D: usize>(points: &[[f64; D]], radius: f64) -> Vec<Vec<usize>> {
+    link_within(points, radius).groups()
+}
+
+/// Welds the vertices of a mesh that lie within `epsilon` of each other,
+/// returning a remap table from the index of every vertex to the index of its
+/// representative, the first vertex it was welded with. Nearby vertices are
+/// found by spatial hashing, as in [`radius_clusters`].
+///
+/// Welding is transitive, so a chain of vertices each within `epsilon` of the
+/// next is welded into one vertex even if its ends lie further apart.
+///
+/// # Panics
+/// Panics if `epsilon` is not positive and finite.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::algorithms::weld_vertices;
+///
+/// let vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1e-7], [1.0, 1e-7, 0.0]];
+/// assert_eq!(weld_vertices(&vertices, 1e-6), vec![0, 1, 0, 1]);
+/// ```
+pub fn weld_vertices(vertices: &[[f64; 3]], epsilon: f64) -> Vec<usize> {
+    let mut sets = link_within(vertices, epsilon);
+    let mut representatives = vec![None; vertices.len()];
+    sets.roots()
+        .into_iter()
+        .enumerate()
+        .map(|(vertex, root)| *representatives[root].get_or_insert(vertex))
+        .collect()
+}
+
+/// Links every two points at most `radius` apart, comparing only the points
+/// of adjacent cells of a grid the size of the radius.
+fn link_within<const D: usize>(points: &[[f64; D]], radius: f64) -> DenseDisjointSet {
+    assert!(radius > 0.0 && radius.is_finite(), "the distance must be positive and finite");
+
+    let cell = |point: &[f64; D]| point.map(|coordinate| (coordinate / radius).floor() as i64);
+    let mut cells: HashMap<[i64; D], Vec<usize>> = HashMap::new();
+    for (index, point) in points.iter().enumerate() {
+        cells.entry(cell(point)).or_default().push(index);
+    }
+
+    let mut sets = DenseDisjointSet::new(points.len());
+    let adjacent = 3usize.pow(D as u32);
+    for (index, point) in points.iter().enumerate() {
+        let home = cell(point);
+        for offset in 0..adjacent {
+            // Decode the offset as base 3 digits of -1, 0 or 1 per axis.
+            let (mut neighbor, mut digits) = (home, offset);
+            for coordinate in &mut neighbor {
+                *coordinate += (digits % 3) as i64 - 1;
+                digits /= 3;
+            }
+
+            for &other in cells.get(&neighbor).into_iter().flatten() {
+                let distance: f64 =
+                    point.iter().zip(&points[other]).map(|(a, b)| (a - b) * (a - b)).sum();
+                if other < index && distance <= radius * radius {
+                    sets.union(index, other);
+                }
+            }
+        }
+    }
+    sets
+}
+
+/// Groups records that share an identifier, directly or through a chain of
+/// other records, and returns the indices of the records of every group.
+/// Groups, and their members, are ordered by index. A record without any
+/// identifiers forms a group of its own.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::algorithms::merge_records;
+///
+/// let records = vec![
+///     vec!["ann@example.com", "+1 555 0100"],
+///     vec!["bob@example.com"],
+///     vec!["device-7", "ann@work.example"],
+///     vec!["+1 555 0100", "device-7"],
+///     vec![],
+/// ];
+/// assert_eq!(merge_records(records), vec![vec![0, 2, 3], vec![1], vec![4]]);
+/// ```
+pub fn merge_records<K, R, I>(records: I) -> Vec<Vec<usize>>
+where
+    K: Eq + Hash,
+    R: IntoIterator<Item = K>,
+    I: IntoIterator<Item = R>,
+{
+    let (mut owners, mut sets) = (HashMap::new(), DenseDisjointSet::new(0));
+    for identifiers in records {
+        let record = sets.push();
+        for identifier in identifiers {
+            let owner = *owners.entry(identifier).or_insert(record);
+            sets.union(owner, record);
+        }
+    }
+    sets.groups()
+}
+
+/// Computes the elimination tree of a sparse symmetric matrix from the row
+/// indices of the nonzero entries of every column: the parent of every column
+/// in the Cholesky factor, or `None` for roots.
+///
+/// Entries below the diagonal are ignored, so either the full pattern or its
+/// upper triangle can be given. Runs in almost linear time in the number of
+/// entries, with the ancestors found so far kept as a disjoint set.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::algorithms::elimination_tree;
+///
+/// // An arrow matrix: every column is coupled to the last one only, and
+/// // column 2 to column 1 as well.
+/// let columns = vec![vec![0], vec![1], vec![1, 2], vec![0, 1, 2, 3]];
+/// assert_eq!(elimination_tree(&columns), vec![Some(3), Some(2), Some(3), None]);
+/// ```
+pub fn elimination_tree(columns: &[Vec<usize>]) -> Vec<Option<usize>> {
+    let mut sets = DenseDisjointSet::new(columns.len());
+    // The root of the subtree of every set, by set root.
+    let mut tops: Vec<usize> = (0..columns.len()).collect();
+    let mut parents = vec![None; columns.len()];
+
+    for (column, rows) in columns.iter().enumerate() {
+        for &row in rows.iter().filter(|&&row| row < column) {
+            let root = sets.find(row);
+            if tops[root] == column {
+                continue;
+            }
+            parents[tops[root]] = Some(column);
+            if let Some((root, _)) = sets.union(root, column) {
+                tops[root] = column;
+            }
+        }
+    }
+    parents
+}
+
+/// The basins of an image flooded by [`watershed`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Watershed<T> {
+    /// The basin of every pixel in row-major order. Basins are numbered in
+    /// the order of their first pixels.
+    pub labels: Vec<usize>,
+    /// Every basin, as it was left once the whole image was flooded.
+    pub basins: Vec<Basin<T>>,
+}
+
+/// A basin of a [`watershed`], as passed to its merge criterion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Basin<T> {
+    /// The number of pixels flooded so far.
+    pub area: usize,
+    /// The lowest value of any of its pixels.
+    pub minimum: T,
+}
+
+/// Segments an image buffer of `width` pixels per row, in row-major order,
+/// into basins by flooding it from its lowest values up.
+///
+/// Pixels are processed in order of increasing value. A pixel with no flooded
+/// neighbour starts a new basin. Otherwise it joins the deepest basin among
+/// its flooded neighbours, and every other basin it touches is merged into
+/// that one if `merge(deepest, other, value)` approves, where `value` is that
+/// of the pixel, the level of the pass between the two basins. A criterion
+/// refusing every merge yields one basin per regional minimum, while a
+/// criterion on the depth `value - other.minimum` or the area of the
+/// shallower basin suppresses insignificant minima.
+///
+/// # Panics
+/// Panics if the buffer does not hold a whole number of rows.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{Connectivity, algorithms::watershed};
+///
+/// // Three valleys: a deep one, a shallow one near it, and another deep one.
+/// let pixels = [0, 5, 4, 5, 9, 1, 9];
+///
+/// let basins = watershed(&pixels, 7, Connectivity::Four, |_, _, _| false);
+/// assert_eq!(basins.labels, vec![0, 0, 1, 1, 2, 2, 2]);
+///
+/// // Merge basins less than 2 deep at the pass between them.
+/// let basins =
+///     watershed(&pixels, 7, Connectivity::Four, |_, other, value| value - other.minimum < 2);
+/// assert_eq!(basins.labels, vec![0, 0, 0, 0, 0, 1, 1]);
+/// assert_eq!(basins.basins[0].area, 5);
+/// ```
+pub fn watershed<T, F>(
+    pixels: &[T],
+    width: usize,
+    connectivity: Connectivity,
+    mut merge: F,
+) -> Watershed<T>
+where
+    T: Ord + Copy,
+    F: FnMut(Basin<T>, Basin<T>, T) -> bool,
+{
+    assert!(pixels.len().is_multiple_of(width), "the image must hold a whole number of rows");
+
+    let mut order: Vec<usize> = (0..pixels.len()).collect();
+    order.sort_by_key(|&pixel| pixels[pixel]);
+
+    let mut sets = DenseDisjointSet::new(pixels.len());
+    // The basin of every set, by root, once one of its pixels is flooded.
+    let mut basins: Vec<Option<Basin<T>>> = vec![None; pixels.len()];
+    for pixel in order {
+        let (x, y) = (pixel % width, pixel / width);
+        let neighbors: Vec<usize> = connectivity
+            .neighbors((x, y))
+            .filter(|&(nx, ny)| nx < width && ny * width < pixels.len())
+            .map(|(nx, ny)| ny * width + nx)
+            .collect();
+        let mut touched = sets.find_batch(&neighbors);
+        touched.retain(|&root| basins[root].is_some());
+        touched.sort_by_key(|&root| (basins[root].map(|basin| basin.minimum), root));
+        touched.dedup();
+
+        let value = pixels[pixel];
+        let Some((&deepest, others)) = touched.split_first() else {
+            basins[pixel] = Some(Basin { area: 1, minimum: value });
+            continue;
+        };
+
+        let joined = basins[deepest].take().expect("flooded");
+        let mut root = sets.union(deepest, pixel).expect("pixel is unflooded").0;
+        basins[root] = Some(Basin { area: joined.area + 1, ..joined });
+        for &other in others {
+            let (basin, other_basin) =
+                (basins[root].expect("flooded"), basins[other].expect("flooded"));
+            if merge(basin, other_basin, value) {
+                basins[root] = None;
+                basins[other] = None;
+                root = sets.union(root, other).expect("basins are distinct").0;
+                basins[root] = Some(Basin { area: basin.area + other_basin.area, ..basin });
+            }
+        }
+    }
+
+    let (mut slots, mut merged) = (vec![None; pixels.len()], Vec::new());
+    let labels = sets
+        .roots()
+        .into_iter()
+        .map(|root| {
+            *slots[root].get_or_insert_with(|| {
+                merged.push(basins[root].expect("every pixel is flooded"));
+                merged.len() - 1
+            })
+        })
+        .collect();
+    Watershed { labels, basins: merged }
+}
+
+/// Computes the components of a binary edge stream in the format of
+/// [`DisjointHashSet::link_edges_binary`] in memory bounded by `chunk_edges`
+/// rather than by the number of keys or edges, and writes the label of every
+/// key to `labels` in the same format: a record of the key and the smallest
+/// key of its component, in key order. Returns the number of components.
+///
+/// The edges are processed as files in `scratch`, by external merge sorts
+/// that hold up to `chunk_edges` edges in memory at a time. Alternating
+/// large-star and small-star rounds, each a sort of the edges and a pass over
+/// them, turn every component into a star around its smallest key in
+/// `O(log² n)` rounds, and in a handful on most graphs. Scratch files are
+/// named uniquely for every call, so that concurrent calls can share
+/// `scratch`, and are deleted once consumed.
+///
+/// # Errors
+/// Fails if reading or writing fails, including with `UnexpectedEof` if the
+/// stream ends inside an edge. Scratch files may be left behind in `scratch`
+/// on failure.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{DisjointHashSet, algorithms::external_components};
+/// use std::num::NonZeroUsize;
+///
+/// let stream = [1u64, 2, 3, 4, 2, 5, 6, 6, 5, 1].map(u64::to_le_bytes).concat();
+/// let scratch = std::env::temp_dir().join(format!("djhs-doc-{}", std::process::id()));
+/// std::fs::create_dir_all(&scratch).unwrap();
+///
+/// let mut labels = Vec::new();
+/// let chunk = NonZeroUsize::new(2).unwrap();
+/// let components = external_components(stream.as_slice(), chunk, &scratch, &mut labels).unwrap();
+/// assert_eq!(components, 3);
+///
+/// let labels: Vec<u64> =
+///     labels.chunks(8).map(|key| u64::from_le_bytes(key.try_into().unwrap())).collect();
+/// assert_eq!(labels, [1, 1, 2, 1, 3, 3, 4, 3, 5, 1, 6, 6]);
+/// std::fs::remove_dir_all(&scratch).unwrap();
+/// ```
+pub fn external_components<R: Read, W: Write>(
+    edges: R,
+    chunk_edges: NonZeroUsize,
+    scratch: &Path,
+    labels: W,
+) -> io::Result<usize> {
+    external::components(edges, chunk_edges.get(), scratch, labels)
+}
@@ -0,0 +1,191 @@
+//! Program analyses built on union-find, such as Steensgaard's points-to
+//! analysis.
+
+use std::{collections::HashMap, hash::Hash};
+
+use crate::dense::DenseDisjointSet;
+
+/// Steensgaard's points-to analysis: a flow-insensitive alias analysis in
+/// almost linear time.
+///
+/// Every variable belongs to a class of abstract locations, and every class
+/// points to at most one other class. Each pointer statement unifies two
+/// classes, and unifying two classes unifies what they point to, so all
+/// constraints are solved as they are added.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::analysis::Steensgaard;
+///
+/// // p = &a; q = &b; r = p; r = q; s = &c;
+/// let mut analysis = Steensgaard::new();
+/// analysis.address_of("p", "a");
+/// analysis.address_of("q", "b");
+/// analysis.assign("r", "p");
+/// analysis.assign("r", "q");
+/// analysis.address_of("s", "c");
+///
+/// assert!(analysis.may_alias("p", "q"));
+/// assert!(!analysis.may_alias("p", "s"));
+///
+/// let mut targets = analysis.points_to("p");
+/// targets.sort();
+/// assert_eq!(targets, vec![&"a", &"b"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Steensgaard<V> {
+    classes: DenseDisjointSet,
+    /// The node of every variable.
+    nodes: HashMap<V, usize>,
+    /// The variable of every node, or `None` for a location created to be
+    /// pointed to.
+    vars: Vec<Option<V>>,
+    /// The node pointed to by every class, by root. Not necessarily a root
+    /// itself.
+    targets: Vec<Option<usize>>,
+}
+
+impl<V: Eq + Hash + Clone> Steensgaard<V> {
+    /// Creates a `Steensgaard` analysis without variables.
+    pub fn new() -> Self {
+        Self {
+            classes: DenseDisjointSet::new(0),
+            nodes: HashMap::new(),
+            vars: Vec::new(),
+            targets: Vec::new(),
+        }
+    }
+
+    /// Adds the statement `pointer = &target`.
+    pub fn address_of(&mut self, pointer: V, target: V) {
+        let (pointer, target) = (self.node(pointer), self.node(target));
+        let pointee = self.pointee(pointer);
+        self.unify_nodes(pointee, target);
+    }
+
+    /// Adds the statement `pointer = source`, a copy of one pointer into
+    /// another.
+    pub fn assign(&mut self, pointer: V, source: V) {
+        let (pointer, source) = (self.node(pointer), self.node(source));
+        let pointees = (self.pointee(pointer), self.pointee(source));
+        self.unify_nodes(pointees.0, pointees.1);
+    }
+
+    /// Adds the statement `pointer = *source`.
+    pub fn load(&mut self, pointer: V, source: V) {
+        let (pointer, source) = (self.node(pointer), self.node(source));
+        let pointee = self.pointee(source);
+        let pointees = (self.pointee(pointer), self.pointee(pointee));
+        self.unify_nodes(pointees.0, pointees.1);
+    }
+
+    /// Adds the statement `*pointer = source`.
+    ///
+    /// ```
+    /// use disjoint_hash_set::analysis::Steensgaard;
+    ///
+    /// // p = &a; q = &p; *q = &b — so `p` may point to `b`.
+    /// let mut analysis = Steensgaard::new();
+    /// analysis.address_of("p", "a");
+    /// analysis.address_of("q", "p");
+    /// analysis.address_of("t", "b");
+    /// analysis.store("q", "t");
+    ///
+    /// assert!(analysis.points_to("p").contains(&&"b"));
+    /// ```
+    pub fn store(&mut self, pointer: V, source: V) {
+        let (pointer, source) = (self.node(pointer), self.node(source));
+        let pointee = self.pointee(pointer);
+        let pointees = (self.pointee(pointee), self.pointee(source));
+        self.unify_nodes(pointees.0, pointees.1);
+    }
+
+    /// Unifies the classes of the two variables directly, along with what
+    /// they point to.
+    pub fn unify(&mut self, var1: V, var2: V) {
+        let nodes = (self.node(var1), self.node(var2));
+        self.unify_nodes(nodes.0, nodes.1);
+    }
+
+    /// Checks if the two variables may point to the same location. This will
+    /// not implicitly add variables that were not already present.
+    pub fn may_alias(&mut self, var1: V, var2: V) -> bool {
+        let (Some(&node1), Some(&node2)) = (self.nodes.get(&var1), self.nodes.get(&var2)) else {
+            return false;
+        };
+        let roots = (self.classes.find(node1), self.classes.find(node2));
+        match (self.targets[roots.0], self.targets[roots.1]) {
+            (Some(target1), Some(target2)) => self.classes.is_linked(target1, target2),
+            _ => false,
+        }
+    }
+
+    /// Returns the variables the variable may point to, in no particular
+    /// order.
+    pub fn points_to(&mut self, var: V) -> Vec<&V> {
+        let Some(&node) = self.nodes.get(&var) else {
+            return Vec::new();
+        };
+        let root = self.classes.find(node);
+        let Some(target) = self.targets[root] else {
+            return Vec::new();
+        };
+        let target = self.classes.find(target);
+        (0..self.vars.len())
+            .filter(|&node| self.classes.find(node) == target)
+            .filter_map(|node| self.vars[node].as_ref())
+            .collect()
+    }
+
+    /// Returns the node of the variable, adding it if needed.
+    fn node(&mut self, var: V) -> usize {
+        if let Some(&node) = self.nodes.get(&var) {
+            return node;
+        }
+        let node = self.push(Some(var.clone()));
+        self.nodes.insert(var, node);
+        node
+    }
+
+    fn push(&mut self, var: Option<V>) -> usize {
+        self.vars.push(var);
+        self.targets.push(None);
+        self.classes.push()
+    }
+
+    /// Returns the node the class of the node points to, creating a location
+    /// to point to if it points nowhere yet.
+    fn pointee(&mut self, node: usize) -> usize {
+        let root = self.classes.find(node);
+        match self.targets[root] {
+            Some(target) => target,
+            None => {
+                let target = self.push(None);
+                self.targets[root] = Some(target);
+                target
+            }
+        }
+    }
+
+    /// Joins the classes of the two nodes, and then, transitively, the
+    /// classes they point to.
+    fn unify_nodes(&mut self, node1: usize, node2: usize) {
+        let mut pending = vec![(node1, node2)];
+        while let Some((node1, node2)) = pending.pop() {
+            let Some((root, child)) = self.classes.union(node1, node2) else {
+                continue;
+            };
+            match (self.targets[root], self.targets[child].take()) {
+                (Some(target1), Some(target2)) => pending.push((target1, target2)),
+                (None, target) => self.targets[root] = target,
+                (Some(_), None) => {}
+            }
+        }
+    }
+}
+
+impl<V: Eq + Hash + Clone> Default for Steensgaard<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
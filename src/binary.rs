@@ -0,0 +1,121 @@
+use std::{
+    hash::{BuildHasher, RandomState},
+    io::{self, Read, Write},
+};
+
+use crate::{DisjointHashSet, LinkSummary};
+
+/// The length of an edge of the binary format: two little-endian `u64` keys.
+pub(crate) const EDGE_LEN: usize = 16;
+
+impl DisjointHashSet<u64, RandomState> {
+    /// Reads a stream of edges in the binary format of
+    /// [`link_edges_binary`](Self::link_edges_binary) into a new
+    /// `DisjointHashSet`.
+    ///
+    /// # Errors
+    /// Fails as by [`link_edges_binary`](Self::link_edges_binary).
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut stream = Vec::new();
+    /// for (a, b) in [(1u64, 2u64), (3, 4), (2, 5)] {
+    ///     stream.extend(a.to_le_bytes());
+    ///     stream.extend(b.to_le_bytes());
+    /// }
+    ///
+    /// let mut djhs = DisjointHashSet::from_edges_binary(stream.as_slice()).unwrap();
+    /// assert!(djhs.is_linked(1, 5));
+    /// assert_eq!(djhs.set_count(), 2);
+    /// ```
+    pub fn from_edges_binary<R: Read>(reader: R) -> io::Result<Self> {
+        let mut djhs = Self::new();
+        djhs.link_edges_binary(reader)?;
+        Ok(djhs)
+    }
+}
+
+impl<S: BuildHasher> DisjointHashSet<u64, S> {
+    /// Links every edge of a binary stream: fixed-width records of two
+    /// little-endian `u64` keys, with no header. An edge of a key to itself
+    /// inserts the key alone.
+    ///
+    /// The stream is read in large chunks, so the reader needs no buffering of
+    /// its own.
+    ///
+    /// # Errors
+    /// Fails if reading fails, or with `UnexpectedEof` if the stream ends in
+    /// the middle of an edge. The edges before it are linked either way.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    /// use std::io::ErrorKind;
+    ///
+    /// let stream = [7u64, 8, 8, 9].map(u64::to_le_bytes).concat();
+    /// let mut djhs = DisjointHashSet::new();
+    /// let summary = djhs.link_edges_binary(stream.as_slice()).unwrap();
+    /// assert_eq!(summary.merged, 2);
+    ///
+    /// let truncated = &stream[..20];
+    /// let error = djhs.link_edges_binary(truncated).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    /// ```
+    pub fn link_edges_binary<R: Read>(&mut self, mut reader: R) -> io::Result<LinkSummary> {
+        let mut summary = LinkSummary::default();
+        let (mut buf, mut filled) = (vec![0; EDGE_LEN << 12], 0);
+        loop {
+            match reader.read(&mut buf[filled..]) {
+                Ok(0) => break,
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            }
+
+            let whole = filled - filled % EDGE_LEN;
+            for edge in buf[..whole].chunks_exact(EDGE_LEN) {
+                let (key1, key2) = edge.split_at(EDGE_LEN / 2);
+                let ids = (self.id_or_insert(decode(key1)), self.id_or_insert(decode(key2)));
+                if self.link_ids(ids) {
+                    summary.merged += 1;
+                } else {
+                    summary.redundant += 1;
+                }
+            }
+            buf.copy_within(whole..filled, 0);
+            filled -= whole;
+        }
+
+        if filled != 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "stream ends inside an edge"));
+        }
+        Ok(summary)
+    }
+
+    /// Writes the [canonical edges](Self::canonical_edges) of the partition in
+    /// the binary format of [`link_edges_binary`](Self::link_edges_binary),
+    /// from which the partition and the order of its keys are restored.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![(5u64, 6u64), (7, 7), (6, 8)]);
+    /// let mut stream = Vec::new();
+    /// djhs.write_edges_binary(&mut stream).unwrap();
+    ///
+    /// let restored = DisjointHashSet::from_edges_binary(stream.as_slice()).unwrap();
+    /// assert_eq!(restored.canonical_edges(), djhs.canonical_edges());
+    /// ```
+    pub fn write_edges_binary<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for (key1, key2) in self.canonical_edges() {
+            writer.write_all(&key1.to_le_bytes())?;
+            writer.write_all(&key2.to_le_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().expect("a key is 8 bytes"))
+}
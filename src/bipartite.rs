@@ -0,0 +1,211 @@
+use std::hash::{BuildHasher, Hash, Hasher, RandomState};
+
+use crate::{DisjointHashSet, PointerId};
+
+/// A key of a [`BipartiteDisjointHashSet`], of either type. Left and right
+/// keys never compare equal, even if their values do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Side<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<L: Hash, R: Hash> Hash for Side<L, R> {
+    /// Hashes as [`side_hash`] does, so that keys can be looked up by
+    /// reference.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Side::Left(key) => {
+                state.write_u8(0);
+                key.hash(state);
+            }
+            Side::Right(key) => {
+                state.write_u8(1);
+                key.hash(state);
+            }
+        }
+    }
+}
+
+/// A partition of keys of two different types, such as users and the
+/// devices they use, with typed accessors for either type.
+///
+/// Keys of both types may be linked with each other, and every set is
+/// reported as its members of either type separately, so that neither type
+/// has to be wrapped in an enum of the caller's.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::BipartiteDisjointHashSet;
+///
+/// #[derive(Debug, PartialEq, Eq, Hash)]
+/// struct UserId(u32);
+/// #[derive(Debug, PartialEq, Eq, Hash)]
+/// struct DeviceId(&'static str);
+///
+/// let mut accounts = BipartiteDisjointHashSet::new();
+/// accounts.link(UserId(1), DeviceId("phone"));
+/// accounts.link(UserId(2), DeviceId("phone"));
+/// accounts.link(UserId(3), DeviceId("laptop"));
+///
+/// assert!(accounts.is_linked_left(&UserId(1), &UserId(2)));
+/// assert_eq!(accounts.lefts_of(&DeviceId("laptop")), vec![&UserId(3)]);
+/// assert_eq!(
+///     accounts.groups(),
+///     vec![
+///         (vec![&UserId(1), &UserId(2)], vec![&DeviceId("phone")]),
+///         (vec![&UserId(3)], vec![&DeviceId("laptop")]),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct BipartiteDisjointHashSet<L, R, S = RandomState> {
+    set: DisjointHashSet<Side<L, R>, S>,
+}
+
+impl<L: Eq + Hash, R: Eq + Hash> BipartiteDisjointHashSet<L, R, RandomState> {
+    /// Creates an empty `BipartiteDisjointHashSet`.
+    pub fn new() -> Self {
+        Self { set: DisjointHashSet::new() }
+    }
+}
+
+impl<L: Eq + Hash, R: Eq + Hash> Default for BipartiteDisjointHashSet<L, R, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L: Eq + Hash, R: Eq + Hash, S: BuildHasher> BipartiteDisjointHashSet<L, R, S> {
+    /// Creates an empty `BipartiteDisjointHashSet` which will use the given
+    /// hash builder to hash keys.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self { set: DisjointHashSet::with_hasher(hash_builder) }
+    }
+
+    /// Check if the left value has already been inserted.
+    pub fn contains_left(&self, val: &L) -> bool {
+        self.left_id(val).is_some()
+    }
+
+    /// Check if the right value has already been inserted.
+    pub fn contains_right(&self, val: &R) -> bool {
+        self.right_id(val).is_some()
+    }
+
+    /// Insert the left value as a new disjoint set with a single member.
+    /// Returns true if the value was not already present.
+    pub fn insert_left(&mut self, val: L) -> bool {
+        self.set.insert(Side::Left(val))
+    }
+
+    /// Insert the right value as a new disjoint set with a single member.
+    /// Returns true if the value was not already present.
+    pub fn insert_right(&mut self, val: R) -> bool {
+        self.set.insert(Side::Right(val))
+    }
+
+    /// Link the respective sets of a left and a right value. This will insert
+    /// non-existent values in the process.
+    pub fn link(&mut self, left: L, right: R) {
+        self.set.link(Side::Left(left), Side::Right(right))
+    }
+
+    /// Link the respective sets of two left values. This will insert
+    /// non-existent values in the process.
+    pub fn link_left(&mut self, val1: L, val2: L) {
+        self.set.link(Side::Left(val1), Side::Left(val2))
+    }
+
+    /// Link the respective sets of two right values. This will insert
+    /// non-existent values in the process.
+    pub fn link_right(&mut self, val1: R, val2: R) {
+        self.set.link(Side::Right(val1), Side::Right(val2))
+    }
+
+    /// Checks if a left and a right key are members of the same set. This
+    /// will not implicitly add values that were not already present.
+    pub fn is_linked(&mut self, left: &L, right: &R) -> bool {
+        self.is_linked_ids(self.left_id(left), self.right_id(right))
+    }
+
+    /// Checks if two left keys are members of the same set. This will not
+    /// implicitly add values that were not already present.
+    pub fn is_linked_left(&mut self, val1: &L, val2: &L) -> bool {
+        self.is_linked_ids(self.left_id(val1), self.left_id(val2))
+    }
+
+    /// Checks if two right keys are members of the same set. This will not
+    /// implicitly add values that were not already present.
+    pub fn is_linked_right(&mut self, val1: &R, val2: &R) -> bool {
+        self.is_linked_ids(self.right_id(val1), self.right_id(val2))
+    }
+
+    /// The number of disjoint sets.
+    pub fn set_count(&self) -> usize {
+        self.set.set_count()
+    }
+
+    /// Returns the right members of the set of the left value, in insertion
+    /// order, or an empty Vec if the value is not present.
+    pub fn rights_of(&self, val: &L) -> Vec<&R> {
+        self.members(self.left_id(val)).1
+    }
+
+    /// Returns the left members of the set of the right value, in insertion
+    /// order, or an empty Vec if the value is not present.
+    pub fn lefts_of(&self, val: &R) -> Vec<&L> {
+        self.members(self.right_id(val)).0
+    }
+
+    /// Returns every disjoint set as its left and its right members, ordered
+    /// by their first inserted members. Either side of a set may be empty.
+    pub fn groups(&self) -> Vec<(Vec<&L>, Vec<&R>)> {
+        self.set.grouped_ids().into_iter().map(|ids| self.split(ids)).collect()
+    }
+
+    fn is_linked_ids(&mut self, id1: Option<PointerId>, id2: Option<PointerId>) -> bool {
+        match (id1, id2) {
+            (Some(id1), Some(id2)) => self.set.find(id1) == self.set.find(id2),
+            _ => false,
+        }
+    }
+
+    /// Returns the members of the set of the id, split by type.
+    fn members(&self, id: Option<PointerId>) -> (Vec<&L>, Vec<&R>) {
+        let Some(root) = id.map(|id| self.set.root(id)) else {
+            return (Vec::new(), Vec::new());
+        };
+        let ids = (0..self.set.keys.len()).map(PointerId).filter(|&id| self.set.root(id) == root);
+        self.split(ids)
+    }
+
+    fn split<I: IntoIterator<Item = PointerId>>(&self, ids: I) -> (Vec<&L>, Vec<&R>) {
+        let (mut lefts, mut rights) = (Vec::new(), Vec::new());
+        for id in ids {
+            match self.set.key(id) {
+                Side::Left(key) => lefts.push(key),
+                Side::Right(key) => rights.push(key),
+            }
+        }
+        (lefts, rights)
+    }
+
+    fn left_id(&self, val: &L) -> Option<PointerId> {
+        let hash = side_hash(&self.set.hash_builder, 0, val);
+        self.set.index.get(hash, |id| matches!(self.set.key(id), Side::Left(key) if key == val))
+    }
+
+    fn right_id(&self, val: &R) -> Option<PointerId> {
+        let hash = side_hash(&self.set.hash_builder, 1, val);
+        self.set.index.get(hash, |id| matches!(self.set.key(id), Side::Right(key) if key == val))
+    }
+}
+
+/// Hashes a key of the given side by reference, as its [`Side`] would be.
+fn side_hash<S: BuildHasher, K: Hash>(hash_builder: &S, side: u8, key: &K) -> u64 {
+    let mut state = hash_builder.build_hasher();
+    state.write_u8(side);
+    key.hash(&mut state);
+    state.finish()
+}
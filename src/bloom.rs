@@ -0,0 +1,107 @@
+use std::{
+    collections::TryReserveError,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::DisjointHashSet;
+
+/// The bits of the filter for every key, for a false positive rate of about
+/// one percent.
+const BITS_PER_KEY: usize = 10;
+/// The number of bits set for every key, optimal for `BITS_PER_KEY`.
+const PROBES: u64 = 7;
+
+/// A Bloom filter over key hashes, ruling out most absent keys without
+/// probing the index. It grows along with the keys, so that the false
+/// positive rate stays bounded.
+#[derive(Debug, Clone)]
+pub(crate) struct BloomFilter {
+    /// The bits, a power of two of them.
+    words: Vec<u64>,
+    /// The number of hashes inserted.
+    len: usize,
+}
+
+impl BloomFilter {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self { words: vec![0; Self::word_count(capacity)], len: 0 }
+    }
+
+    /// Creates a filter as `with_capacity` does, failing rather than aborting
+    /// if memory can't be allocated.
+    pub(crate) fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        let (mut words, count) = (Vec::new(), Self::word_count(capacity));
+        words.try_reserve_exact(count)?;
+        words.resize(count, 0);
+        Ok(Self { words, len: 0 })
+    }
+
+    /// Checks if the filter is holding as many hashes as it was sized for.
+    pub(crate) fn is_full(&self) -> bool {
+        !self.has_room(0)
+    }
+
+    /// Checks if the filter can take `additional` more hashes before it is
+    /// full.
+    pub(crate) fn has_room(&self, additional: usize) -> bool {
+        (self.len + additional) * BITS_PER_KEY < self.words.len() * 64
+    }
+
+    pub(crate) fn insert(&mut self, hash: u64) {
+        for bit in self.bits(hash) {
+            self.words[bit / 64] |= 1 << (bit % 64);
+        }
+        self.len += 1;
+    }
+
+    fn word_count(capacity: usize) -> usize {
+        (capacity.max(1) * BITS_PER_KEY).div_ceil(64).next_power_of_two()
+    }
+
+    /// Checks if a hash may have been inserted. False only if it never was.
+    pub(crate) fn may_contain(&self, hash: u64) -> bool {
+        self.bits(hash).all(|bit| self.words[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// The bits of a hash, derived from two halves of it by double hashing.
+    fn bits(&self, hash: u64) -> impl Iterator<Item = usize> {
+        let shift = 64 - (self.words.len() * 64).trailing_zeros();
+        let step = hash.rotate_left(32) | 1;
+        (0..PROBES).map(move |i| {
+            let mixed = hash.wrapping_add(i.wrapping_mul(step)).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            (mixed >> shift) as usize
+        })
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Starts checking lookups against a Bloom filter of the key hashes, so
+    /// that most lookups of absent keys, such as by
+    /// [`contains`](Self::contains) or [`is_linked`](Self::is_linked), are
+    /// answered without probing the index or comparing keys.
+    ///
+    /// Keys are still hashed, and the filter costs about 10 bits per key, so
+    /// this pays off when most lookups miss and the index is too large for
+    /// the cache. A lookup of a present key is slightly slower.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::from_iter((0..1000).map(|i| (i, i + 1)));
+    /// djhs.enable_bloom_filter();
+    /// djhs.link(5000, 5001);
+    ///
+    /// assert!(djhs.contains(5001));
+    /// assert!(!djhs.contains(2000));
+    /// assert!(!djhs.is_linked(0, 2000));
+    /// assert!(djhs.is_linked(0, 1000));
+    /// ```
+    pub fn enable_bloom_filter(&mut self) {
+        self.index.enable_bloom_filter();
+    }
+
+    /// Stops checking lookups against a Bloom filter and discards it.
+    pub fn disable_bloom_filter(&mut self) {
+        self.index.disable_bloom_filter();
+    }
+}
@@ -0,0 +1,79 @@
+use std::{
+    hash::{BuildHasher, Hash, RandomState},
+    iter::FusedIterator,
+};
+
+use crate::DisjointHashSet;
+
+impl<'a> DisjointHashSet<&'a str, RandomState> {
+    /// Creates a `DisjointHashSet` of the whitespace separated tokens of the
+    /// text, linking the tokens of every line. The keys borrow from the text,
+    /// so no token is copied.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let corpus = String::from("alice bob\ncarol\nbob  dave\n");
+    /// let mut djhs = DisjointHashSet::from_lines(&corpus);
+    /// assert!(djhs.is_linked("alice", "dave"));
+    /// assert!(!djhs.is_linked("alice", "carol"));
+    ///
+    /// // Copy the groups out before the corpus goes away.
+    /// let sets = djhs.into_owned_sets().collect::<Vec<Vec<String>>>();
+    /// drop(corpus);
+    /// assert_eq!(sets, vec![vec!["alice", "bob", "dave"], vec!["carol"]]);
+    /// ```
+    pub fn from_lines(text: &'a str) -> Self {
+        let mut djhs = Self::new();
+        for line in text.lines() {
+            let mut tokens = line.split_whitespace();
+            let Some(first) = tokens.next() else { continue };
+            let first = djhs.id_or_insert(first);
+            for token in tokens {
+                let id = djhs.id_or_insert(token);
+                djhs.link_ids((first, id));
+            }
+        }
+        djhs
+    }
+}
+
+impl<T, S> DisjointHashSet<&T, S>
+where
+    T: ?Sized + Eq + Hash + ToOwned,
+    S: BuildHasher,
+{
+    /// Consumes the `DisjointHashSet` of borrowed keys, returning every
+    /// disjoint set as a Vec of owned copies of its members, as by
+    /// [`ordered_sets`](Self::ordered_sets).
+    pub fn into_owned_sets(self) -> impl ExactSizeIterator<Item = Vec<T::Owned>> + FusedIterator
+    where
+        T::Owned: Eq + Hash,
+    {
+        self.into_owned().ordered_sets()
+    }
+
+    /// Consumes the `DisjointHashSet` of borrowed keys, returning one of owned
+    /// copies of its keys. The partition, its trees and any recorded history
+    /// are kept as they are, as owned keys hash like the keys they borrow
+    /// from.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::from_lines("a b\nc d");
+    /// let mut owned: DisjointHashSet<String> = djhs.into_owned();
+    /// assert!(owned.is_linked("c".to_string(), "d".to_string()));
+    /// owned.link("b".to_string(), "c".to_string());
+    /// assert_eq!(owned.set_count(), 1);
+    /// ```
+    pub fn into_owned(self) -> DisjointHashSet<T::Owned, S> {
+        DisjointHashSet {
+            keys: self.keys.into_iter().map(T::to_owned).collect(),
+            index: self.index,
+            hash_builder: self.hash_builder,
+            forest: self.forest,
+        }
+    }
+}
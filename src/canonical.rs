@@ -0,0 +1,80 @@
+use std::{
+    fmt::{Display, Write},
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{DisjointHashSet, PointerId, SeededState};
+
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Computes a hash of the partition that depends only on which keys are
+    /// linked, not on the order of insertions and links, the shape of the
+    /// trees or the hasher, so that equal partitions hash equally.
+    ///
+    /// Keys are hashed with a [`SeededState`] of a fixed seed, so the hash is
+    /// also stable across processes and platforms for keys whose `Hash`
+    /// implementation is, such as integers and strings.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let djhs1 = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d"), ("b", "e")]);
+    /// let djhs2 = DisjointHashSet::from_iter(vec![("d", "c"), ("e", "a"), ("a", "b")]);
+    /// let djhs3 = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "e"), ("b", "d")]);
+    ///
+    /// assert_eq!(djhs1.canonical_hash(), djhs2.canonical_hash());
+    /// assert_ne!(djhs1.canonical_hash(), djhs3.canonical_hash());
+    /// ```
+    pub fn canonical_hash(&self) -> u64 {
+        let state = SeededState::new(0);
+
+        // Sums are commutative, so neither the order of sets nor of their
+        // members matters, and every sum is mixed before it is summed again.
+        let mut sets = vec![(0u64, 0usize); self.forest.len()];
+        for (id, key) in self.keys.iter().enumerate() {
+            let set = &mut sets[self.root(PointerId(id)).0];
+            set.0 = set.0.wrapping_add(state.hash_one(key));
+            set.1 += 1;
+        }
+
+        let (sum, count) = sets
+            .into_iter()
+            .filter(|&(_, size)| size > 0)
+            .fold((0u64, 0usize), |(sum, count), set| {
+                (sum.wrapping_add(state.hash_one(set)), count + 1)
+            });
+        state.hash_one((sum, count))
+    }
+}
+
+impl<K: Eq + Hash + Ord + Display, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Renders the partition as text that depends only on which keys are
+    /// linked, for golden-file tests and diffs: a line for every set, with its
+    /// members sorted, and the sets sorted by their smallest members.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![(3, 1), (5, 4), (2, 2), (4, 6)]);
+    /// assert_eq!(djhs.to_canonical_string(), "{1, 3}\n{2}\n{4, 5, 6}\n");
+    /// ```
+    pub fn to_canonical_string(&self) -> String {
+        let mut sets: Vec<Vec<&K>> = self
+            .grouped_ids()
+            .into_iter()
+            .map(|set| set.into_iter().map(|id| self.key(id)).collect())
+            .collect();
+        sets.iter_mut().for_each(|set| set.sort_unstable());
+        sets.sort_unstable();
+
+        let mut text = String::new();
+        for set in sets {
+            text.push('{');
+            for (index, key) in set.into_iter().enumerate() {
+                let separator = if index > 0 { ", " } else { "" };
+                write!(text, "{separator}{key}").expect("writing to a String can't fail");
+            }
+            text.push_str("}\n");
+        }
+        text
+    }
+}
@@ -0,0 +1,185 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::dense::DenseDisjointSet;
+
+/// A term added to a [`CongruenceClosure`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TermId(usize);
+
+/// Equalities between terms, closed under congruence: two applications of
+/// the same symbol to equal children are equal themselves.
+///
+/// Terms are added bottom-up, as a symbol applied to previously added
+/// children, and [merging](Self::merge) two terms merges, transitively, every
+/// pair of parents that became congruent. Adding a term congruent to one
+/// already present returns the present term.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::CongruenceClosure;
+///
+/// // From f(f(f(a))) = a and f(f(f(f(f(a))))) = a follows f(a) = a.
+/// let mut cc = CongruenceClosure::new();
+/// let a = cc.add("a", &[]);
+/// let mut f = vec![a];
+/// for i in 0..5 {
+///     f.push(cc.add("f", &[f[i]]));
+/// }
+///
+/// cc.merge(f[3], a);
+/// assert!(!cc.is_equal(f[1], a));
+/// cc.merge(f[5], a);
+/// assert!(cc.is_equal(f[1], a));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CongruenceClosure<F> {
+    classes: DenseDisjointSet,
+    /// The symbol and the children of every term.
+    terms: Vec<(F, Vec<TermId>)>,
+    /// The term of every signature, a symbol applied to the representatives
+    /// of children.
+    signatures: HashMap<(F, Vec<TermId>), TermId>,
+    /// For every representative, the terms with a child in its class.
+    uses: Vec<Vec<TermId>>,
+}
+
+impl<F: Eq + Hash + Clone> CongruenceClosure<F> {
+    /// Creates a `CongruenceClosure` without terms.
+    pub fn new() -> Self {
+        Self {
+            classes: DenseDisjointSet::new(0),
+            terms: Vec::new(),
+            signatures: HashMap::new(),
+            uses: Vec::new(),
+        }
+    }
+
+    /// Adds the application of `symbol` to `children`, a constant if there
+    /// are none, returning the term. If a congruent term is already present,
+    /// it is returned instead.
+    ///
+    /// # Panics
+    /// Panics if a child is not a term of this structure.
+    ///
+    /// ```
+    /// use disjoint_hash_set::CongruenceClosure;
+    ///
+    /// let mut cc = CongruenceClosure::new();
+    /// let (x, y) = (cc.add("x", &[]), cc.add("y", &[]));
+    /// let sum = cc.add("+", &[x, y]);
+    /// assert_eq!(cc.add("+", &[x, y]), sum);
+    ///
+    /// cc.merge(x, y);
+    /// let double = cc.add("+", &[y, y]);
+    /// assert!(cc.is_equal(double, sum));
+    /// ```
+    pub fn add(&mut self, symbol: F, children: &[TermId]) -> TermId {
+        let signature = signature(&mut self.classes, &symbol, children);
+        if let Some(&term) = self.signatures.get(&signature) {
+            return term;
+        }
+
+        let term = TermId(self.classes.push());
+        self.uses.push(Vec::new());
+        for child in &signature.1 {
+            self.uses[child.0].push(term);
+        }
+        self.signatures.insert(signature, term);
+        self.terms.push((symbol, children.to_vec()));
+        term
+    }
+
+    /// Declares the two terms equal, along with every pair of terms that
+    /// becomes congruent as a consequence.
+    ///
+    /// # Panics
+    /// Panics if a term is not a term of this structure.
+    pub fn merge(&mut self, term1: TermId, term2: TermId) {
+        let mut pending = vec![(term1, term2)];
+        while let Some((term1, term2)) = pending.pop() {
+            let roots = (self.classes.find(term1.0), self.classes.find(term2.0));
+            if roots.0 == roots.1 {
+                continue;
+            }
+
+            // The signatures of the terms using either class are about to
+            // change, so they are taken out and reinserted after the union.
+            let parents: Vec<TermId> = [roots.0, roots.1]
+                .into_iter()
+                .flat_map(|root| std::mem::take(&mut self.uses[root]))
+                .collect();
+            for &parent in &parents {
+                let (symbol, children) = &self.terms[parent.0];
+                let signature = signature(&mut self.classes, symbol, children);
+                if self.signatures.get(&signature) == Some(&parent) {
+                    self.signatures.remove(&signature);
+                }
+            }
+
+            let (root, _) = self.classes.union(roots.0, roots.1).expect("roots are distinct");
+            for &parent in &parents {
+                let (symbol, children) = &self.terms[parent.0];
+                let signature = signature(&mut self.classes, symbol, children);
+                match self.signatures.get(&signature) {
+                    Some(&congruent) => pending.push((parent, congruent)),
+                    None => {
+                        self.signatures.insert(signature, parent);
+                    }
+                }
+            }
+            self.uses[root] = parents;
+        }
+    }
+
+    /// Checks if the two terms are equal.
+    ///
+    /// # Panics
+    /// Panics if a term is not a term of this structure.
+    pub fn is_equal(&mut self, term1: TermId, term2: TermId) -> bool {
+        self.classes.is_linked(term1.0, term2.0)
+    }
+
+    /// Returns the representative of the class of the term, a term shared by
+    /// every term equal to it.
+    ///
+    /// # Panics
+    /// Panics if the term is not a term of this structure.
+    pub fn representative(&mut self, term: TermId) -> TermId {
+        TermId(self.classes.find(term.0))
+    }
+
+    /// Returns the symbol and the children the term was added with.
+    ///
+    /// # Panics
+    /// Panics if the term is not a term of this structure.
+    pub fn term(&self, term: TermId) -> (&F, &[TermId]) {
+        let (symbol, children) = &self.terms[term.0];
+        (symbol, children)
+    }
+
+    /// The number of terms.
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// Checks if no term has been added.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+}
+
+impl<F: Eq + Hash + Clone> Default for CongruenceClosure<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The signature of an application: its symbol and the representatives of its
+/// children.
+fn signature<F: Clone>(
+    classes: &mut DenseDisjointSet,
+    symbol: &F,
+    children: &[TermId],
+) -> (F, Vec<TermId>) {
+    (symbol.clone(), children.iter().map(|child| TermId(classes.find(child.0))).collect())
+}
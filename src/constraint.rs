@@ -0,0 +1,288 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    error::Error,
+    fmt,
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+use crate::{DisjointHashSet, PointerId};
+
+/// A `DisjointHashSet` enforcing cannot-link constraints between keys.
+///
+/// Once two keys are declared with [`cannot_link`](Self::cannot_link), any
+/// link that would join their sets fails with a [`ConstraintViolation`] and
+/// leaves the partition unchanged. Checking a link costs a `find` for every
+/// constraint on the smaller of the two sets.
+///
+/// A [maximum set size](Self::set_max_set_size) can also be configured, so
+/// that a single bad link can't merge everything into one runaway set.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::ConstrainedDisjointHashSet;
+///
+/// let mut djhs = ConstrainedDisjointHashSet::new();
+/// djhs.cannot_link("cat", "dog").unwrap();
+///
+/// djhs.link("cat", "kitten").unwrap();
+/// djhs.link("dog", "puppy").unwrap();
+/// assert!(djhs.link("kitten", "puppy").is_err());
+/// assert!(!djhs.is_linked("cat", "dog"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConstrainedDisjointHashSet<K, S = RandomState> {
+    set: DisjointHashSet<K, S>,
+    /// For every root, the ids its set must never be joined with.
+    constraints: HashMap<PointerId, Vec<PointerId>>,
+    max_set_size: Option<usize>,
+}
+
+impl<K: Eq + Hash> ConstrainedDisjointHashSet<K, RandomState> {
+    /// Creates an empty `ConstrainedDisjointHashSet`.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::ConstrainedDisjointHashSet;
+    /// let mut djhs: ConstrainedDisjointHashSet<&str> = ConstrainedDisjointHashSet::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Eq + Hash> Default for ConstrainedDisjointHashSet<K, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> ConstrainedDisjointHashSet<K, S> {
+    /// Creates an empty `ConstrainedDisjointHashSet` which will use the given
+    /// hash builder to hash keys.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::hash::RandomState;
+    /// use disjoint_hash_set::ConstrainedDisjointHashSet;
+    /// let s = RandomState::new();
+    /// let mut djhs: ConstrainedDisjointHashSet<&str> = ConstrainedDisjointHashSet::with_hasher(s);
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            set: DisjointHashSet::with_hasher(hash_builder),
+            constraints: HashMap::new(),
+            max_set_size: None,
+        }
+    }
+
+    /// Check if the value has already been inserted.
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.set.contains(val)
+    }
+
+    /// Insert the value as a new disjoint set with a single member. Returns
+    /// true if the value was not already present.
+    pub fn insert(&mut self, val: K) -> bool {
+        self.set.insert(val)
+    }
+
+    /// Checks if the two keys are members of the same set.
+    /// This will not implicitly add values that were not already present.
+    pub fn is_linked<T: Borrow<K>>(&mut self, val1: T, val2: T) -> bool {
+        self.set.is_linked(val1, val2)
+    }
+
+    /// Declares that the sets of the two values must never be joined. This
+    /// will insert non-existent values in the process. Fails, returning the
+    /// values with [`Violation::AlreadyLinked`], if they are equal or already
+    /// members of the same set.
+    ///
+    /// ```
+    /// use disjoint_hash_set::ConstrainedDisjointHashSet;
+    ///
+    /// let mut djhs = ConstrainedDisjointHashSet::new();
+    /// djhs.link("a", "b").unwrap();
+    /// assert!(djhs.cannot_link("a", "c").is_ok());
+    /// assert!(djhs.cannot_link("b", "a").is_err());
+    /// assert!(djhs.cannot_link("d", "d").is_err());
+    /// ```
+    pub fn cannot_link(&mut self, val1: K, val2: K) -> Result<(), ConstraintViolation<K>> {
+        if val1 == val2 || self.set.is_linked(&val1, &val2) {
+            return Err(ConstraintViolation(val1, val2, Violation::AlreadyLinked));
+        }
+
+        let ids = (self.set.id_or_insert(val1), self.set.id_or_insert(val2));
+        let roots = (self.set.find(ids.0), self.set.find(ids.1));
+        self.constraints.entry(roots.0).or_default().push(ids.1);
+        self.constraints.entry(roots.1).or_default().push(ids.0);
+        Ok(())
+    }
+
+    /// Limits the number of keys of any set, so that links that would join
+    /// two sets into a larger one fail. `None` removes the limit. Sets that
+    /// are already larger are left as they are.
+    ///
+    /// A link can be forced past the limit with
+    /// [`link_uncapped`](Self::link_uncapped).
+    ///
+    /// ```
+    /// use disjoint_hash_set::ConstrainedDisjointHashSet;
+    ///
+    /// let mut djhs = ConstrainedDisjointHashSet::new();
+    /// djhs.set_max_set_size(Some(3));
+    /// djhs.link("a", "b").unwrap();
+    /// djhs.link("b", "c").unwrap();
+    /// assert!(djhs.link("c", "d").is_err());
+    /// assert!(!djhs.contains("d"));
+    ///
+    /// djhs.set_max_set_size(None);
+    /// assert!(djhs.link("c", "d").is_ok());
+    /// ```
+    pub fn set_max_set_size(&mut self, max_set_size: Option<usize>) {
+        self.max_set_size = max_set_size;
+    }
+
+    /// The maximum number of keys of any set, if limited.
+    pub fn max_set_size(&self) -> Option<usize> {
+        self.max_set_size
+    }
+
+    /// Link the respective sets of the two provided values. This will insert
+    /// non-existent values in the process. Fails, returning the values and
+    /// leaving the partition unchanged, if the link would join two keys
+    /// declared with [`cannot_link`](Self::cannot_link), or join two sets into
+    /// one larger than the [maximum set size](Self::set_max_set_size).
+    ///
+    /// ```
+    /// use disjoint_hash_set::{ConstrainedDisjointHashSet, ConstraintViolation, Violation};
+    ///
+    /// let mut djhs = ConstrainedDisjointHashSet::new();
+    /// djhs.cannot_link(1, 2).unwrap();
+    /// djhs.link(2, 3).unwrap();
+    /// assert_eq!(djhs.link(3, 1), Err(ConstraintViolation(3, 1, Violation::CannotLink)));
+    ///
+    /// djhs.set_max_set_size(Some(2));
+    /// let exceeded = Violation::SetSizeExceeded { size: 3, max: 2 };
+    /// assert_eq!(djhs.link(3, 4), Err(ConstraintViolation(3, 4, exceeded)));
+    /// ```
+    pub fn link(&mut self, val1: K, val2: K) -> Result<(), ConstraintViolation<K>> {
+        if self.violates(&val1, &val2) {
+            return Err(ConstraintViolation(val1, val2, Violation::CannotLink));
+        }
+        if let Some(size) = self.joined_size_over_max(&val1, &val2) {
+            let max = self.max_set_size.expect("sizes are only exceeded under a maximum");
+            return Err(ConstraintViolation(val1, val2, Violation::SetSizeExceeded { size, max }));
+        }
+        self.join(val1, val2);
+        Ok(())
+    }
+
+    /// Links the respective sets of the two provided values as by
+    /// [`link`](Self::link), but past the [maximum set
+    /// size](Self::set_max_set_size), for links known to be sound. Cannot-link
+    /// constraints are still enforced.
+    ///
+    /// ```
+    /// use disjoint_hash_set::ConstrainedDisjointHashSet;
+    ///
+    /// let mut djhs = ConstrainedDisjointHashSet::new();
+    /// djhs.set_max_set_size(Some(2));
+    /// djhs.cannot_link("a", "z").unwrap();
+    /// djhs.link("a", "b").unwrap();
+    ///
+    /// assert!(djhs.link("b", "c").is_err());
+    /// assert!(djhs.link_uncapped("b", "c").is_ok());
+    /// assert!(djhs.link_uncapped("c", "z").is_err());
+    /// assert_eq!(djhs.max_set_size(), Some(2));
+    /// ```
+    pub fn link_uncapped(&mut self, val1: K, val2: K) -> Result<(), ConstraintViolation<K>> {
+        if self.violates(&val1, &val2) {
+            return Err(ConstraintViolation(val1, val2, Violation::CannotLink));
+        }
+        self.join(val1, val2);
+        Ok(())
+    }
+
+    /// Consumes the structure, returning the underlying `DisjointHashSet`.
+    pub fn into_inner(self) -> DisjointHashSet<K, S> {
+        self.set
+    }
+
+    /// Joins the sets of the two values, moving the constraints of the
+    /// absorbed root to the surviving one.
+    fn join(&mut self, val1: K, val2: K) {
+        let ids = (self.set.id_or_insert(val1), self.set.id_or_insert(val2));
+        if let Some((root, child)) = self.set.union(ids.0, ids.1) {
+            if let Some(mut absorbed) = self.constraints.remove(&child) {
+                self.constraints.entry(root).or_default().append(&mut absorbed);
+            }
+        }
+    }
+
+    /// Returns the size of the set joining the sets of the two values, if it
+    /// would exceed the maximum set size. Keys without a set count as sets of
+    /// their own.
+    fn joined_size_over_max(&mut self, val1: &K, val2: &K) -> Option<usize> {
+        let max_set_size = self.max_set_size?;
+        let mut root = |val| self.set.id(val).map(|id| self.set.find(id));
+        let roots = (root(val1), root(val2));
+        if val1 == val2 || (roots.0.is_some() && roots.0 == roots.1) {
+            return None;
+        }
+
+        let size = |root: Option<PointerId>| root.map_or(1, |root| self.set.get(root).size);
+        Some(size(roots.0) + size(roots.1)).filter(|&size| size > max_set_size)
+    }
+
+    /// Checks if joining the sets of the two values would violate a
+    /// constraint. Keys without a set have no constraints.
+    fn violates(&mut self, val1: &K, val2: &K) -> bool {
+        let (Some(id1), Some(id2)) = (self.set.id(val1), self.set.id(val2)) else {
+            return false;
+        };
+        let roots = (self.set.find(id1), self.set.find(id2));
+        if roots.0 == roots.1 {
+            return false;
+        }
+
+        let len = |root| self.constraints.get(&root).map_or(0, Vec::len);
+        let (root, other) = if len(roots.0) <= len(roots.1) { roots } else { (roots.1, roots.0) };
+
+        self.constraints
+            .get(&root)
+            .is_some_and(|partners| partners.iter().any(|&id| self.set.root(id) == other))
+    }
+}
+
+/// The keys of a refused link or cannot-link declaration, and why it was
+/// refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstraintViolation<K>(pub K, pub K, pub Violation);
+
+/// The reason a [`ConstraintViolation`] was raised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Violation {
+    /// The link would have joined keys declared cannot-link.
+    CannotLink,
+    /// The link would have joined two sets into one of `size` keys, more than
+    /// the maximum set size `max`.
+    SetSizeExceeded { size: usize, max: usize },
+    /// The cannot-link declaration was between keys that are already linked,
+    /// or between a key and itself.
+    AlreadyLinked,
+}
+
+impl<K> fmt::Display for ConstraintViolation<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.2 {
+            Violation::CannotLink => f.write_str("link would join keys declared cannot-link"),
+            Violation::SetSizeExceeded { size, max } => {
+                write!(f, "link would create a set of {size} keys, more than the maximum of {max}")
+            }
+            Violation::AlreadyLinked => f.write_str("cannot-link keys are already linked"),
+        }
+    }
+}
+
+impl<K: fmt::Debug> Error for ConstraintViolation<K> {}
@@ -0,0 +1,134 @@
+use std::{
+    fmt::Display,
+    hash::{BuildHasher, Hash, RandomState},
+    io::{self, BufRead, Write},
+};
+
+use crate::DisjointHashSet;
+
+/// The dialect of the files read by [`DisjointHashSet::read_edges_csv`] and
+/// written by [`DisjointHashSet::write_labels_csv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CsvOptions {
+    /// The byte separating fields, a comma by default.
+    pub delimiter: u8,
+    /// Whether the first row is a header, true by default. A header is skipped
+    /// when reading and written when writing.
+    pub has_headers: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self { delimiter: b',', has_headers: true }
+    }
+}
+
+impl DisjointHashSet<String, RandomState> {
+    /// Reads an edge list with a pair of keys per row, linking the two keys of
+    /// every row. A row with a single key inserts it alone, fields past the
+    /// second, such as weights, are ignored, and empty rows are skipped.
+    ///
+    /// Fields may be quoted, with quotes inside them doubled, but can't span
+    /// several lines.
+    ///
+    /// # Errors
+    /// Fails if reading fails, or with `InvalidData` if a row has an unclosed
+    /// quote or isn't UTF-8.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{CsvOptions, DisjointHashSet};
+    ///
+    /// let csv = "source;target\nann;bob\n\"c;d\";bob\neve\n";
+    /// let options = CsvOptions { delimiter: b';', ..CsvOptions::default() };
+    /// let mut djhs = DisjointHashSet::read_edges_csv(csv.as_bytes(), &options).unwrap();
+    ///
+    /// assert!(djhs.is_linked("ann".to_string(), "c;d".to_string()));
+    /// assert!(djhs.contains("eve".to_string()));
+    /// assert_eq!(djhs.set_count(), 2);
+    /// ```
+    pub fn read_edges_csv<R: BufRead>(reader: R, options: &CsvOptions) -> io::Result<Self> {
+        let mut djhs = Self::new();
+        let skip = usize::from(options.has_headers);
+        for (number, line) in reader.lines().enumerate().skip(skip) {
+            let fields = split_fields(&line?, char::from(options.delimiter)).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unclosed quote on line {}", number + 1),
+                )
+            })?;
+
+            let mut fields = fields.into_iter();
+            match (fields.next(), fields.next()) {
+                (Some(key1), Some(key2)) => djhs.link(key1, key2),
+                (Some(key), None) if !key.is_empty() => {
+                    djhs.insert(key);
+                }
+                _ => {}
+            }
+        }
+        Ok(djhs)
+    }
+}
+
+impl<K: Eq + Hash + Display, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Writes a row for every key, in insertion order, with the key and the
+    /// number of its set. Sets are numbered from `0` in the order of their
+    /// first keys.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{CsvOptions, DisjointHashSet};
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("x,y", "x,y"), ("b", "c")]);
+    /// let mut csv = Vec::new();
+    /// djhs.write_labels_csv(&mut csv, &CsvOptions::default()).unwrap();
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "key,component\na,0\nb,0\n\"x,y\",1\nc,0\n");
+    /// ```
+    pub fn write_labels_csv<W: Write>(
+        &self,
+        mut writer: W,
+        options: &CsvOptions,
+    ) -> io::Result<()> {
+        let delimiter = char::from(options.delimiter);
+        if options.has_headers {
+            writeln!(writer, "key{delimiter}component")?;
+        }
+
+        for (key, label) in self.keys.iter().zip(self.component_ids()) {
+            writeln!(writer, "{}{delimiter}{label}", quote(&key.to_string(), delimiter))?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits a row into its fields, or returns `None` if a quote is unclosed.
+fn split_fields(line: &str, delimiter: char) -> Option<Vec<String>> {
+    let (mut fields, mut field, mut quoted) = (Vec::new(), String::new(), false);
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+    }
+    Some(fields)
+}
+
+/// Quotes the field if it holds the delimiter, a quote or a line break.
+fn quote(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
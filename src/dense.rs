@@ -0,0 +1,114 @@
+/// The number of indices [`DenseDisjointSet::find_batch`] walks at once.
+const LANES: usize = 8;
+
+/// A disjoint set over the indices `0..len`, for dense keys such as the cells
+/// of a grid, which need no hashing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DenseDisjointSet {
+    parents: Vec<usize>,
+    /// The number of members of every set, maintained for roots only.
+    sizes: Vec<usize>,
+}
+
+impl DenseDisjointSet {
+    pub(crate) fn new(len: usize) -> Self {
+        Self { parents: (0..len).collect(), sizes: vec![1; len] }
+    }
+
+    /// Adds an index as a new set with a single member, returning it.
+    pub(crate) fn push(&mut self) -> usize {
+        self.parents.push(self.parents.len());
+        self.sizes.push(1);
+        self.parents.len() - 1
+    }
+
+    pub(crate) fn is_linked(&mut self, index1: usize, index2: usize) -> bool {
+        self.find(index1) == self.find(index2)
+    }
+
+    /// Joins the sets of the two indices by size, returning the surviving
+    /// root and the root attached beneath it, or `None` if they were already
+    /// members of the same set.
+    pub(crate) fn union(&mut self, index1: usize, index2: usize) -> Option<(usize, usize)> {
+        let roots = (self.find(index1), self.find(index2));
+        if roots.0 == roots.1 {
+            return None;
+        }
+
+        let (root, child) =
+            if self.sizes[roots.0] < self.sizes[roots.1] { (roots.1, roots.0) } else { roots };
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        Some((root, child))
+    }
+
+    /// Finds the root of the index, halving the path along the way.
+    pub(crate) fn find(&mut self, mut index: usize) -> usize {
+        while self.parents[index] != index {
+            let grandparent = self.parents[self.parents[index]];
+            self.parents[index] = grandparent;
+            index = grandparent;
+        }
+        index
+    }
+
+    /// Finds the roots of many indices at once, compressing their paths.
+    ///
+    /// The indices are walked `LANES` at a time, advancing every lane by one
+    /// parent per step, so that the loads of different lanes are independent
+    /// and their cache misses overlap rather than being waited on one by one.
+    pub(crate) fn find_batch(&mut self, indices: &[usize]) -> Vec<usize> {
+        let mut roots = Vec::with_capacity(indices.len());
+        for chunk in indices.chunks(LANES) {
+            let mut lanes = [0; LANES];
+            lanes[..chunk.len()].copy_from_slice(chunk);
+            loop {
+                let mut done = true;
+                for lane in &mut lanes {
+                    let parent = self.parents[*lane];
+                    done &= parent == *lane;
+                    *lane = parent;
+                }
+                if done {
+                    break;
+                }
+            }
+            for (&index, &root) in chunk.iter().zip(&lanes) {
+                self.parents[index] = root;
+            }
+            roots.extend_from_slice(&lanes[..chunk.len()]);
+        }
+        roots
+    }
+
+    /// Returns the root of every index, fully compressing every path.
+    ///
+    /// Rather than finding each index in turn, every pass replaces each parent
+    /// by its grandparent, reading from one buffer and writing to another so
+    /// that the pass vectorizes as a gather. Union by size bounds trees to a
+    /// logarithmic height, so `O(log log n)` passes flatten every tree.
+    pub(crate) fn roots(&mut self) -> Vec<usize> {
+        let mut next = vec![0; self.parents.len()];
+        loop {
+            let parents = &self.parents;
+            next.iter_mut().zip(parents).for_each(|(next, &parent)| *next = parents[parent]);
+            std::mem::swap(&mut self.parents, &mut next);
+            if self.parents == next {
+                return next;
+            }
+        }
+    }
+
+    /// Returns the members of every set, ordered by their smallest members.
+    pub(crate) fn groups(&mut self) -> Vec<Vec<usize>> {
+        let (mut slots, mut groups) = (vec![None; self.parents.len()], Vec::<Vec<usize>>::new());
+        for (index, root) in self.roots().into_iter().enumerate() {
+            let slot = *slots[root].get_or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[slot].push(index);
+        }
+        groups
+    }
+}
@@ -0,0 +1,98 @@
+use std::hash::{BuildHasher, Hasher};
+
+/// A `BuildHasher` whose hashes depend only on a seed, for results that are
+/// reproducible across runs and platforms.
+///
+/// The partition, and the representative of every set, never depend on the
+/// hasher. Hashing only decides the order in which hash based collections
+/// iterate, such as the sets returned by
+/// [`sets_with_hasher`](crate::DisjointHashSet::sets_with_hasher). With a
+/// `SeededState` that order is fixed for a given seed and sequence of
+/// operations. Unlike `RandomState`, it offers no protection against keys
+/// chosen to collide.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{DisjointHashSet, SeededState};
+///
+/// let run = || {
+///     let mut djhs = DisjointHashSet::with_hasher(SeededState::new(42));
+///     (0..100).for_each(|i| djhs.link(i, i % 7));
+///     djhs.sets_with_hasher(SeededState::new(42))
+///         .map(|set| set.into_iter().collect::<Vec<_>>())
+///         .collect::<Vec<_>>()
+/// };
+/// assert_eq!(run(), run());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SeededState {
+    seed: u64,
+}
+
+impl SeededState {
+    /// Creates a `SeededState` from the seed.
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl BuildHasher for SeededState {
+    type Hasher = SeededHasher;
+
+    fn build_hasher(&self) -> SeededHasher {
+        SeededHasher { state: self.seed }
+    }
+}
+
+/// The hasher created by a [`SeededState`]. Integers are hashed as little
+/// endian `u64`s regardless of platform.
+#[derive(Debug, Clone)]
+pub struct SeededHasher {
+    state: u64,
+}
+
+impl Hasher for SeededHasher {
+    fn finish(&self) -> u64 {
+        // The splitmix64 finalizer.
+        let mut hash = self.state;
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        hash ^ (hash >> 31)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.write_u64(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+
+        let mut rest = [0; 8];
+        rest[..chunks.remainder().len()].copy_from_slice(chunks.remainder());
+        self.write_u64(u64::from_le_bytes(rest) ^ ((bytes.len() as u64) << 56));
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.state = (self.state ^ i).wrapping_mul(0x9e37_79b9_7f4a_7c15).rotate_left(29);
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write_u64(i as u64);
+        self.write_u64((i >> 64) as u64);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    fmt::{self, Display, Write},
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{DisjointHashSet, PointerId};
+
+/// What [`DisjointHashSet::to_dot`] renders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DotView {
+    /// The internal forest, with an edge from every key to its parent.
+    Forest,
+    /// The disjoint sets, as clustered subgraphs in the order of their first
+    /// keys.
+    Sets,
+}
+
+impl<K: Eq + Hash + Display, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Renders the structure in the Graphviz DOT language, labelling nodes
+    /// with the keys.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{DisjointHashSet, DotView};
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "c")]);
+    /// assert_eq!(
+    ///     djhs.to_dot(DotView::Forest),
+    ///     "digraph {\n  n0 [label=\"a\"];\n  n1 [label=\"b\"];\n  n2 [label=\"c\"];\n  n1 -> n0;\n}\n"
+    /// );
+    /// assert!(djhs.to_dot(DotView::Sets).contains("subgraph cluster_1 {\n    n2;\n  }"));
+    /// ```
+    pub fn to_dot(&self, view: DotView) -> String {
+        let mut dot = String::new();
+        self.write_dot(&mut dot, view).expect("writing to a String can't fail");
+        dot
+    }
+
+    /// Writes the structure in the Graphviz DOT language, as by
+    /// [`to_dot`](Self::to_dot).
+    pub fn write_dot<W: Write>(&self, writer: &mut W, view: DotView) -> fmt::Result {
+        writeln!(writer, "digraph {{")?;
+        for (id, key) in self.keys.iter().enumerate() {
+            writeln!(writer, "  n{id} [label=\"{}\"];", Escaped(key))?;
+        }
+
+        match view {
+            DotView::Forest => {
+                for id in
+                    (0..self.forest.len()).map(PointerId).filter(|&id| self.get(id).parent != id)
+                {
+                    writeln!(writer, "  n{} -> n{};", id.0, self.get(id).parent.0)?;
+                }
+            }
+            DotView::Sets => {
+                for (cluster, set) in self.grouped_ids().into_iter().enumerate() {
+                    writeln!(writer, "  subgraph cluster_{cluster} {{")?;
+                    for id in set {
+                        writeln!(writer, "    n{};", id.0)?;
+                    }
+                    writeln!(writer, "  }}")?;
+                }
+            }
+        }
+        writeln!(writer, "}}")
+    }
+}
+
+/// Displays a key escaped for a quoted DOT string.
+struct Escaped<'a, K>(&'a K);
+
+impl<K: Display> Display for Escaped<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.to_string().chars() {
+            match c {
+                '"' | '\\' => write!(f, "\\{c}")?,
+                '\n' => f.write_str("\\n")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
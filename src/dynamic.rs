@@ -0,0 +1,460 @@
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+/// A connectivity structure supporting both `link` and `unlink`.
+///
+/// Unlike a `DisjointHashSet`, which can only ever join sets, links can be
+/// removed again and the keys they connected split apart if no other path
+/// joins them. A spanning forest of the links is kept as Euler tour trees, so
+/// connectivity queries and links cost `O(log n)` expected. Removing a link
+/// that isn't part of the spanning forest is equally cheap. Removing one that
+/// is costs `O(k log n)`, where `k` is the number of links from the smaller of
+/// the two resulting components that are searched for a replacement.
+///
+/// Links are counted, so a pair of keys linked twice stays linked until it is
+/// unlinked twice.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::DynamicDisjointHashSet;
+///
+/// let mut djhs = DynamicDisjointHashSet::new();
+/// djhs.link("a", "b");
+/// djhs.link("b", "c");
+/// djhs.link("c", "a");
+///
+/// djhs.unlink("a", "b");
+/// assert!(djhs.is_linked("a", "b")); // still connected through "c"
+///
+/// djhs.unlink("b", "c");
+/// assert!(!djhs.is_linked("a", "b"));
+/// assert!(djhs.is_linked("a", "c"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DynamicDisjointHashSet<K, S = RandomState> {
+    ids: HashMap<K, usize, S>,
+    edges: HashMap<(usize, usize), Edge>,
+    /// The links of every key that are not part of the spanning forest.
+    non_tree: HashMap<usize, HashSet<usize>>,
+    forest: EulerTourForest,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    count: usize,
+    tree: bool,
+}
+
+impl<K: Eq + Hash> DynamicDisjointHashSet<K, RandomState> {
+    /// Creates an empty `DynamicDisjointHashSet`.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::DynamicDisjointHashSet;
+    /// let mut djhs: DynamicDisjointHashSet<&str> = DynamicDisjointHashSet::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Eq + Hash> Default for DynamicDisjointHashSet<K, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> DynamicDisjointHashSet<K, S> {
+    /// Creates an empty `DynamicDisjointHashSet` which will use the given hash
+    /// builder to hash keys.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::hash::RandomState;
+    /// use disjoint_hash_set::DynamicDisjointHashSet;
+    /// let s = RandomState::new();
+    /// let mut djhs: DynamicDisjointHashSet<&str> = DynamicDisjointHashSet::with_hasher(s);
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            ids: HashMap::with_hasher(hash_builder),
+            edges: HashMap::new(),
+            non_tree: HashMap::new(),
+            forest: EulerTourForest::default(),
+        }
+    }
+
+    /// Check if the value has already been inserted.
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.ids.contains_key(val.borrow())
+    }
+
+    /// Insert the value as a new component with a single member. Returns true
+    /// if the value was not already present.
+    pub fn insert(&mut self, val: K) -> bool {
+        (!self.contains(&val)).then(|| self.insert_unchecked(val)).is_some()
+    }
+
+    /// Checks if the two keys are connected by a path of links.
+    /// This will not implicitly add values that were not already present.
+    pub fn is_linked<T: Borrow<K>>(&self, val1: T, val2: T) -> bool {
+        match (self.ids.get(val1.borrow()), self.ids.get(val2.borrow())) {
+            (Some(&id1), Some(&id2)) => self.forest.connected(id1, id2),
+            _ => false,
+        }
+    }
+
+    /// Links the two values. This will insert non-existent values in the
+    /// process.
+    pub fn link(&mut self, val1: K, val2: K) {
+        let ids = (self.id_or_insert(val1), self.id_or_insert(val2));
+        if ids.0 == ids.1 {
+            return;
+        }
+
+        let key = (ids.0.min(ids.1), ids.0.max(ids.1));
+        if let Some(edge) = self.edges.get_mut(&key) {
+            edge.count += 1;
+            return;
+        }
+
+        let tree = !self.forest.connected(ids.0, ids.1);
+        if tree {
+            self.forest.link(ids.0, ids.1);
+        } else {
+            self.add_non_tree(ids.0, ids.1);
+        }
+        self.edges.insert(key, Edge { count: 1, tree });
+    }
+
+    /// Removes one link between the two values, splitting their component if
+    /// no other path connects them. Returns true if the values were linked
+    /// directly.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DynamicDisjointHashSet;
+    ///
+    /// let mut djhs = DynamicDisjointHashSet::new();
+    /// djhs.link(1, 2);
+    /// djhs.link(2, 1);
+    /// assert!(djhs.unlink(1, 2));
+    /// assert!(djhs.is_linked(1, 2));
+    /// assert!(djhs.unlink(2, 1));
+    /// assert!(!djhs.is_linked(1, 2));
+    /// assert!(!djhs.unlink(1, 2));
+    /// ```
+    pub fn unlink<T: Borrow<K>>(&mut self, val1: T, val2: T) -> bool {
+        let (Some(&id1), Some(&id2)) = (self.ids.get(val1.borrow()), self.ids.get(val2.borrow()))
+        else {
+            return false;
+        };
+
+        let key = (id1.min(id2), id1.max(id2));
+        let Some(edge) = self.edges.get_mut(&key) else {
+            return false;
+        };
+
+        edge.count -= 1;
+        if edge.count > 0 {
+            return true;
+        }
+
+        let tree = edge.tree;
+        self.edges.remove(&key);
+        if tree {
+            self.forest.cut(id1, id2);
+            self.replace(id1, id2);
+        } else {
+            self.remove_non_tree(id1, id2);
+        }
+        true
+    }
+
+    /// After the tree link between `id1` and `id2` was cut, searches the
+    /// smaller of their components for a link reconnecting the two.
+    fn replace(&mut self, id1: usize, id2: usize) {
+        let small = if self.forest.vertices(id1) <= self.forest.vertices(id2) { id1 } else { id2 };
+
+        for id in self.forest.vertices_with_non_tree(small) {
+            let replacement = self.non_tree[&id]
+                .iter()
+                .copied()
+                .find(|&other| !self.forest.connected(small, other));
+
+            if let Some(other) = replacement {
+                self.remove_non_tree(id, other);
+                self.forest.link(id, other);
+                self.edges.get_mut(&(id.min(other), id.max(other))).unwrap().tree = true;
+                return;
+            }
+        }
+    }
+
+    fn add_non_tree(&mut self, id1: usize, id2: usize) {
+        for (id, other) in [(id1, id2), (id2, id1)] {
+            let links = self.non_tree.entry(id).or_default();
+            links.insert(other);
+            self.forest.set_non_tree(id, links.len());
+        }
+    }
+
+    fn remove_non_tree(&mut self, id1: usize, id2: usize) {
+        for (id, other) in [(id1, id2), (id2, id1)] {
+            let links = self.non_tree.get_mut(&id).unwrap();
+            links.remove(&other);
+            self.forest.set_non_tree(id, links.len());
+            if links.is_empty() {
+                self.non_tree.remove(&id);
+            }
+        }
+    }
+
+    fn id_or_insert(&mut self, value: K) -> usize {
+        self.ids.get(&value).copied().unwrap_or_else(|| self.insert_unchecked(value))
+    }
+
+    fn insert_unchecked(&mut self, value: K) -> usize {
+        let id = self.forest.add_vertex();
+        self.ids.insert(value, id);
+        id
+    }
+}
+
+const NIL: usize = usize::MAX;
+
+/// A forest of Euler tours, each stored as a treap ordered by position in the
+/// tour. Every vertex has a node of its own, and every tree edge a node for
+/// each of its two directions.
+#[derive(Debug, Clone, Default)]
+struct EulerTourForest {
+    nodes: Vec<TourNode>,
+    free: Vec<usize>,
+    /// The node of every directed tree edge.
+    arcs: HashMap<(usize, usize), usize>,
+    seed: u64,
+}
+
+#[derive(Debug, Clone)]
+struct TourNode {
+    left: usize,
+    right: usize,
+    parent: usize,
+    priority: u64,
+    /// Nodes in this subtree.
+    len: usize,
+    /// Vertex nodes in this subtree.
+    vertices: usize,
+    /// Non-tree links of this node, if it is a vertex.
+    non_tree: usize,
+    /// Non-tree links of the vertices in this subtree.
+    sub_non_tree: usize,
+    is_vertex: bool,
+}
+
+impl EulerTourForest {
+    fn add_vertex(&mut self) -> usize {
+        self.alloc(true)
+    }
+
+    fn connected(&self, u: usize, v: usize) -> bool {
+        self.root(u) == self.root(v)
+    }
+
+    /// The number of vertices in the tree containing `u`.
+    fn vertices(&self, u: usize) -> usize {
+        self.nodes[self.root(u)].vertices
+    }
+
+    /// Links the trees of `u` and `v` with a new tree edge.
+    fn link(&mut self, u: usize, v: usize) {
+        let (tour_u, tour_v) = (self.reroot(u), self.reroot(v));
+        let (uv, vu) = (self.alloc(false), self.alloc(false));
+        self.arcs.insert((u, v), uv);
+        self.arcs.insert((v, u), vu);
+
+        let tour = self.merge(tour_u, uv);
+        let tour = self.merge(tour, tour_v);
+        self.merge(tour, vu);
+    }
+
+    /// Cuts the tree edge between `u` and `v`.
+    fn cut(&mut self, u: usize, v: usize) {
+        let (mut uv, mut vu) =
+            (self.arcs.remove(&(u, v)).unwrap(), self.arcs.remove(&(v, u)).unwrap());
+        let (mut i, mut j) = (self.index(uv), self.index(vu));
+        if i > j {
+            (uv, vu, i, j) = (vu, uv, j, i);
+        }
+
+        // The tour is `before uv inner vu after`, where `inner` is the tour of
+        // one side and `before after` the tour of the other.
+        let (before, rest) = self.split(self.root(uv), i);
+        let (_, rest) = self.split(rest, 1);
+        let (_, rest) = self.split(rest, j - i - 1);
+        let (_, after) = self.split(rest, 1);
+        self.merge(before, after);
+
+        self.free.extend([uv, vu]);
+    }
+
+    /// Sets the number of non-tree links of the vertex `u`.
+    fn set_non_tree(&mut self, u: usize, count: usize) {
+        self.nodes[u].non_tree = count;
+        let mut node = u;
+        while node != NIL {
+            self.update(node);
+            node = self.nodes[node].parent;
+        }
+    }
+
+    /// The vertices in the tree of `u` that have non-tree links.
+    fn vertices_with_non_tree(&self, u: usize) -> Vec<usize> {
+        let (mut found, mut stack) = (Vec::new(), vec![self.root(u)]);
+        while let Some(node) = stack.pop() {
+            if node == NIL || self.nodes[node].sub_non_tree == 0 {
+                continue;
+            }
+            if self.nodes[node].non_tree > 0 {
+                found.push(node);
+            }
+            stack.extend([self.nodes[node].left, self.nodes[node].right]);
+        }
+        found
+    }
+
+    /// Rotates the tour containing `u` to start at `u`, returning its root.
+    fn reroot(&mut self, u: usize) -> usize {
+        let (front, back) = self.split(self.root(u), self.index(u));
+        self.merge(back, front)
+    }
+
+    fn root(&self, mut node: usize) -> usize {
+        while self.nodes[node].parent != NIL {
+            node = self.nodes[node].parent;
+        }
+        node
+    }
+
+    /// The position of the node within its tour.
+    fn index(&self, mut node: usize) -> usize {
+        let mut index = self.len(self.nodes[node].left);
+        while self.nodes[node].parent != NIL {
+            let parent = self.nodes[node].parent;
+            if self.nodes[parent].right == node {
+                index += self.len(self.nodes[parent].left) + 1;
+            }
+            node = parent;
+        }
+        index
+    }
+
+    /// Splits the treap into its first `k` nodes and the rest.
+    fn split(&mut self, root: usize, k: usize) -> (usize, usize) {
+        if root == NIL {
+            return (NIL, NIL);
+        }
+
+        let left = self.nodes[root].left;
+        let (front, back) = if k <= self.len(left) {
+            let (front, back) = self.split(left, k);
+            self.set_left(root, back);
+            (front, root)
+        } else {
+            let (front, back) = self.split(self.nodes[root].right, k - self.len(left) - 1);
+            self.set_right(root, front);
+            (root, back)
+        };
+
+        self.update(root);
+        self.set_parent(front, NIL);
+        self.set_parent(back, NIL);
+        (front, back)
+    }
+
+    /// Concatenates two treaps, returning the root of the result.
+    fn merge(&mut self, front: usize, back: usize) -> usize {
+        if front == NIL || back == NIL {
+            return if front == NIL { back } else { front };
+        }
+
+        if self.nodes[front].priority > self.nodes[back].priority {
+            let right = self.merge(self.nodes[front].right, back);
+            self.set_right(front, right);
+            self.update(front);
+            front
+        } else {
+            let left = self.merge(front, self.nodes[back].left);
+            self.set_left(back, left);
+            self.update(back);
+            back
+        }
+    }
+
+    fn update(&mut self, node: usize) {
+        let (left, right) = (self.nodes[node].left, self.nodes[node].right);
+        let sum = |f: fn(&TourNode) -> usize| {
+            [left, right].iter().filter(|&&n| n != NIL).map(|&n| f(&self.nodes[n])).sum::<usize>()
+        };
+
+        let (len, vertices, sub_non_tree) =
+            (sum(|n| n.len), sum(|n| n.vertices), sum(|n| n.sub_non_tree));
+        let node = &mut self.nodes[node];
+        node.len = len + 1;
+        node.vertices = vertices + node.is_vertex as usize;
+        node.sub_non_tree = sub_non_tree + node.non_tree;
+    }
+
+    fn len(&self, node: usize) -> usize {
+        if node == NIL { 0 } else { self.nodes[node].len }
+    }
+
+    fn set_left(&mut self, node: usize, left: usize) {
+        self.nodes[node].left = left;
+        self.set_parent(left, node);
+    }
+
+    fn set_right(&mut self, node: usize, right: usize) {
+        self.nodes[node].right = right;
+        self.set_parent(right, node);
+    }
+
+    fn set_parent(&mut self, node: usize, parent: usize) {
+        if node != NIL {
+            self.nodes[node].parent = parent;
+        }
+    }
+
+    fn alloc(&mut self, is_vertex: bool) -> usize {
+        // xorshift64*, deterministic so that repeated runs build equal treaps.
+        self.seed = if self.seed == 0 { 0x853c_49e6_748f_ea9b } else { self.seed };
+        self.seed ^= self.seed >> 12;
+        self.seed ^= self.seed << 25;
+        self.seed ^= self.seed >> 27;
+
+        let node = TourNode {
+            left: NIL,
+            right: NIL,
+            parent: NIL,
+            priority: self.seed.wrapping_mul(0x2545_f491_4f6c_dd1d),
+            len: 1,
+            vertices: is_vertex as usize,
+            non_tree: 0,
+            sub_non_tree: 0,
+            is_vertex,
+        };
+
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+}
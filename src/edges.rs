@@ -0,0 +1,121 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+    iter::FusedIterator,
+};
+
+use crate::{DisjointHashSet, PointerId};
+
+/// A pair of keys passed to `link` while edges are recorded.
+#[derive(Debug, Clone)]
+pub(crate) struct RecordedEdge {
+    pub(crate) ids: (PointerId, PointerId),
+    /// Whether the link joined two previously disjoint sets.
+    pub(crate) merged: bool,
+}
+
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Starts recording every pair of keys passed to `link`, including links
+    /// between keys that were already members of the same set, which can then
+    /// be read back with [`edges`](Self::edges). Edges linked since a
+    /// checkpoint are discarded by [`rollback_to`](Self::rollback_to).
+    ///
+    /// Edges refer to keys by id, so recording does not require keys to be
+    /// cloned.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.record_edges();
+    /// djhs.link("a", "b");
+    /// djhs.link("b", "c");
+    /// djhs.link("c", "a");
+    ///
+    /// // Three edges within a component of three keys.
+    /// let edges: Vec<(&str, &str)> = djhs.edges().map(|(a, b)| (*a, *b)).collect();
+    /// assert_eq!(edges.iter().filter(|(a, _)| djhs.is_linked(*a, "a")).count(), 3);
+    /// ```
+    pub fn record_edges(&mut self) {
+        self.forest.edges.get_or_insert_with(Vec::new);
+    }
+
+    /// Stops recording edges and discards those recorded.
+    pub fn clear_edges(&mut self) {
+        self.forest.edges = None;
+    }
+
+    /// Returns an iterator over the recorded edges in the order they were
+    /// linked. Empty if recording was never started.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link("x", "y");
+    /// djhs.record_edges();
+    /// djhs.link("a", "b");
+    /// djhs.link("b", "a");
+    /// assert_eq!(djhs.edges().collect::<Vec<_>>(), vec![(&"a", &"b"), (&"b", &"a")]);
+    /// ```
+    pub fn edges(&self) -> impl ExactSizeIterator<Item = (&K, &K)> + FusedIterator {
+        self.forest
+            .edges
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .map(|edge| (self.key(edge.ids.0), self.key(edge.ids.1)))
+    }
+
+    /// The number of recorded edges that linked keys which were already
+    /// members of the same set.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.record_edges();
+    /// djhs.link("a", "b");
+    /// djhs.link("b", "a");
+    /// djhs.link("b", "c");
+    /// djhs.link("c", "a");
+    /// assert_eq!(djhs.redundant_edge_count(), 2);
+    /// ```
+    pub fn redundant_edge_count(&self) -> usize {
+        self.forest.edges.iter().flatten().filter(|edge| !edge.merged).count()
+    }
+
+    /// Returns the recorded edges that joined two previously disjoint sets,
+    /// grouped by the representative of the component they belong to. These
+    /// form a spanning forest of the recorded edges: each component of `n`
+    /// keys linked while recording has `n - 1` of them.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.record_edges();
+    /// djhs.link("a", "b");
+    /// djhs.link("b", "c");
+    /// djhs.link("c", "a"); // redundant
+    /// djhs.link("x", "y");
+    ///
+    /// let forest = djhs.spanning_forest_edges();
+    /// let mut sizes: Vec<_> = forest.values().map(Vec::len).collect();
+    /// sizes.sort();
+    /// assert_eq!(sizes, vec![1, 2]);
+    /// assert!(!forest.values().flatten().any(|edge| *edge == (&"c", &"a")));
+    /// ```
+    pub fn spanning_forest_edges(&self) -> HashMap<&K, Vec<(&K, &K)>> {
+        let mut forest: HashMap<&K, Vec<(&K, &K)>> = HashMap::new();
+
+        for edge in self.forest.edges.iter().flatten().filter(|edge| edge.merged) {
+            let representative = self.key(self.root(edge.ids.0));
+            forest
+                .entry(representative)
+                .or_default()
+                .push((self.key(edge.ids.0), self.key(edge.ids.1)));
+        }
+        forest
+    }
+}
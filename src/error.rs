@@ -0,0 +1,228 @@
+use std::{
+    borrow::Borrow,
+    collections::TryReserveError,
+    error, fmt,
+    hash::{BuildHasher, Hash, RandomState},
+    io,
+};
+
+use crate::{
+    ConstrainedDisjointHashSet, ConstraintViolation, Contradiction, DisjointHashSet,
+    IntegrityError, Parts, PointerId, Violation,
+};
+
+/// The failure of a checked operation, such as
+/// [`DisjointHashSet::try_link`], shared across the structures of this crate
+/// so that fallible operations compose with `?`.
+///
+/// The errors of the other fallible operations convert into it: a
+/// [`ConstraintViolation`] by its reason, a [`Contradiction`], an
+/// [`IntegrityError`], and the `io::Error` of loading or writing, which keeps
+/// the `IntegrityError` of rejected data.
+///
+/// ```
+/// use disjoint_hash_set::{ConstrainedDisjointHashSet, DisjointHashSet, Error};
+///
+/// fn import(bytes: &[u8]) -> Result<ConstrainedDisjointHashSet<u32>, Error> {
+///     let loaded = DisjointHashSet::<u32>::load_from_reader(bytes)?;
+///     let mut djhs = ConstrainedDisjointHashSet::new();
+///     djhs.set_max_set_size(Some(2));
+///     for set in loaded.sets() {
+///         let mut set = set.into_iter();
+///         let first = set.next().unwrap();
+///         set.try_for_each(|key| djhs.link(first, key))?;
+///     }
+///     Ok(djhs)
+/// }
+///
+/// let mut bytes = Vec::new();
+/// DisjointHashSet::from_iter([(1u32, 2), (2, 3)]).save_to_writer(&mut bytes).unwrap();
+/// assert_eq!(import(&bytes).unwrap_err(), Error::CapacityExceeded);
+/// assert_eq!(import(&bytes[..4]).unwrap_err(), Error::Io(std::io::ErrorKind::UnexpectedEof));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// A key is not present.
+    MissingKey,
+    /// Memory could not be allocated for another key, or a set would grow
+    /// beyond its maximum size.
+    CapacityExceeded,
+    /// A link would join keys declared cannot-link.
+    ConstraintViolated,
+    /// Restored data violates an invariant of the structure.
+    CorruptData(IntegrityError),
+    /// Reading or writing failed, with an error of the given kind.
+    Io(io::ErrorKind),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingKey => f.write_str("key is not present"),
+            Error::CapacityExceeded => f.write_str("capacity exceeded"),
+            Error::ConstraintViolated => f.write_str("link would violate a constraint"),
+            Error::CorruptData(_) => f.write_str("data is corrupt"),
+            Error::Io(kind) => write!(f, "I/O failed: {kind}"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::CorruptData(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<IntegrityError> for Error {
+    fn from(error: IntegrityError) -> Self {
+        Error::CorruptData(error)
+    }
+}
+
+impl<K> From<ConstraintViolation<K>> for Error {
+    fn from(violation: ConstraintViolation<K>) -> Self {
+        match violation.2 {
+            Violation::CannotLink | Violation::AlreadyLinked => Error::ConstraintViolated,
+            Violation::SetSizeExceeded { .. } => Error::CapacityExceeded,
+        }
+    }
+}
+
+impl<K> From<Contradiction<K>> for Error {
+    fn from(_: Contradiction<K>) -> Self {
+        Error::ConstraintViolated
+    }
+}
+
+impl From<io::Error> for Error {
+    /// Recovers the [`IntegrityError`] of data rejected while loading, and
+    /// keeps the kind of any other I/O error.
+    fn from(error: io::Error) -> Self {
+        match error.get_ref().and_then(|source| source.downcast_ref::<IntegrityError>()) {
+            Some(&integrity) => Error::CorruptData(integrity),
+            None => Error::Io(error.kind()),
+        }
+    }
+}
+
+impl From<TryReserveError> for Error {
+    fn from(_: TryReserveError) -> Self {
+        Error::CapacityExceeded
+    }
+}
+
+impl<K: Eq + Hash> DisjointHashSet<K, RandomState> {
+    /// Restores a `DisjointHashSet` from its raw parts as by
+    /// [`from_parts`](Self::from_parts).
+    ///
+    /// # Errors
+    /// Fails with [`Error::CorruptData`] if the parts are invalid.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{DisjointHashSet, Error, IntegrityError, Parts};
+    ///
+    /// let parts = Parts { keys: vec!["a", "b"], parents: vec![1], ranks: vec![0, 0] };
+    /// let error = DisjointHashSet::try_from_parts(parts).unwrap_err();
+    /// assert_eq!(error, Error::CorruptData(IntegrityError::LengthMismatch));
+    /// ```
+    pub fn try_from_parts(parts: Parts<K>) -> Result<Self, Error> {
+        Ok(Self::from_parts(parts)?)
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Insert the value as a new disjoint set with a single member, as by
+    /// [`insert`](Self::insert). Returns true if the value was not already
+    /// present.
+    ///
+    /// # Errors
+    /// Fails with [`Error::CapacityExceeded`] if memory for the key, its
+    /// index entry or whatever is recorded about it can't be allocated,
+    /// rather than aborting.
+    pub fn try_insert(&mut self, val: K) -> Result<bool, Error> {
+        if self.contains(&val) {
+            return Ok(false);
+        }
+        self.keys.try_reserve(1)?;
+        self.forest.try_reserve(1)?;
+        self.index.try_reserve(1)?;
+        self.insert_unchecked(val);
+        Ok(true)
+    }
+
+    /// Links the respective sets of the two values, both of which must be
+    /// present. Unlike [`link`](Self::link), absent values are not inserted.
+    /// Returns true if the sets were disjoint.
+    ///
+    /// # Errors
+    /// Fails with [`Error::MissingKey`] if either value is not present,
+    /// leaving the partition unchanged.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{DisjointHashSet, Error};
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.insert("a");
+    /// djhs.insert("b");
+    /// assert_eq!(djhs.try_link("a", "b"), Ok(true));
+    /// assert_eq!(djhs.try_link("b", "a"), Ok(false));
+    /// assert_eq!(djhs.try_link("a", "c"), Err(Error::MissingKey));
+    /// assert!(!djhs.contains("c"));
+    /// ```
+    pub fn try_link<T: Borrow<K>>(&mut self, val1: T, val2: T) -> Result<bool, Error> {
+        let ids = (self.try_id(val1.borrow())?, self.try_id(val2.borrow())?);
+        Ok(self.link_ids(ids))
+    }
+
+    /// Checks if the two keys are members of the same set.
+    ///
+    /// # Errors
+    /// Fails with [`Error::MissingKey`] if either value is not present, where
+    /// [`is_linked`](Self::is_linked) returns false.
+    pub fn try_is_linked<T: Borrow<K>>(&mut self, val1: T, val2: T) -> Result<bool, Error> {
+        let ids = (self.try_id(val1.borrow())?, self.try_id(val2.borrow())?);
+        Ok(self.find(ids.0) == self.find(ids.1))
+    }
+
+    /// Returns the representative of the value's set.
+    ///
+    /// # Errors
+    /// Fails with [`Error::MissingKey`] if the value is not present.
+    pub fn try_representative<T: Borrow<K>>(&self, val: T) -> Result<&K, Error> {
+        self.representative(val).ok_or(Error::MissingKey)
+    }
+
+    fn try_id(&self, val: &K) -> Result<PointerId, Error> {
+        self.id(val).ok_or(Error::MissingKey)
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> ConstrainedDisjointHashSet<K, S> {
+    /// Links the respective sets of the two values as by
+    /// [`link`](Self::link), telling the two ways a link can be refused
+    /// apart.
+    ///
+    /// # Errors
+    /// Fails with [`Error::ConstraintViolated`] if the link would join keys
+    /// declared cannot-link, or with [`Error::CapacityExceeded`] if it would
+    /// exceed the maximum set size, leaving the partition unchanged.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{ConstrainedDisjointHashSet, Error};
+    ///
+    /// let mut djhs = ConstrainedDisjointHashSet::new();
+    /// djhs.cannot_link("a", "b").unwrap();
+    /// djhs.set_max_set_size(Some(2));
+    ///
+    /// assert_eq!(djhs.try_link("a", "b"), Err(Error::ConstraintViolated));
+    /// assert_eq!(djhs.try_link("a", "c"), Ok(()));
+    /// assert_eq!(djhs.try_link("c", "d"), Err(Error::CapacityExceeded));
+    /// ```
+    pub fn try_link(&mut self, val1: K, val2: K) -> Result<(), Error> {
+        self.link(val1, val2).map_err(Error::from)
+    }
+}
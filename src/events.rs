@@ -0,0 +1,134 @@
+use std::{
+    hash::{BuildHasher, Hash},
+    iter::FusedIterator,
+};
+
+use crate::{DisjointHashSet, PointerId, forest::Forest};
+
+/// A change to a `DisjointHashSet`, as recorded by
+/// [`DisjointHashSet::record_events`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Event<K> {
+    /// The key was inserted as a new singleton set.
+    Insert(K),
+    /// The keys were linked, joining two previously disjoint sets.
+    Link(K, K),
+}
+
+impl<K> Event<K> {
+    /// Maps the keys of the event with `f`, e.g. to clone borrowed keys.
+    ///
+    /// ```
+    /// use disjoint_hash_set::Event;
+    ///
+    /// let event = Event::Link(&"a", &"b");
+    /// assert_eq!(event.map(|k| k.to_uppercase()), Event::Link("A".into(), "B".into()));
+    /// ```
+    pub fn map<T>(self, mut f: impl FnMut(K) -> T) -> Event<T> {
+        match self {
+            Event::Insert(key) => Event::Insert(f(key)),
+            Event::Link(key1, key2) => Event::Link(f(key1), f(key2)),
+        }
+    }
+}
+
+/// A recorded event, referring to keys by id.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum LoggedEvent {
+    Insert(PointerId),
+    Link(PointerId, PointerId),
+}
+
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Starts recording every insertion and effective union, which can then
+    /// be read back with [`events`](Self::events). Links between keys that
+    /// are already members of the same set are not recorded, and unions
+    /// undone with [`rollback_to`](Self::rollback_to) are removed from the
+    /// log.
+    ///
+    /// Events refer to keys by id, so recording does not require keys to be
+    /// cloned.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{DisjointHashSet, Event};
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.record_events();
+    /// djhs.insert("a");
+    /// djhs.link("a", "b");
+    /// djhs.link("b", "a");
+    ///
+    /// let events: Vec<_> = djhs.events().collect();
+    /// assert_eq!(events, vec![Event::Insert(&"a"), Event::Insert(&"b"), Event::Link(&"a", &"b")]);
+    /// ```
+    pub fn record_events(&mut self) {
+        self.forest.events.get_or_insert_with(Vec::new);
+    }
+
+    /// Stops recording events and discards the log.
+    pub fn clear_events(&mut self) {
+        self.forest.events = None;
+        self.forest.log_epoch += 1;
+    }
+
+    /// Returns an iterator over the recorded events, oldest first. Empty if
+    /// recording was never started.
+    pub fn events(&self) -> impl ExactSizeIterator<Item = Event<&K>> + FusedIterator {
+        self.forest.events.as_deref().unwrap_or_default().iter().map(|event| match *event {
+            LoggedEvent::Insert(id) => Event::Insert(self.key(id)),
+            LoggedEvent::Link(id1, id2) => Event::Link(self.key(id1), self.key(id2)),
+        })
+    }
+
+    /// Applies a sequence of events, such as a log exported from another
+    /// structure with [`events`](Self::events). Replaying a complete log into
+    /// a fresh structure reproduces the original exactly, down to the choice
+    /// of representatives.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.record_events();
+    /// djhs.link("a", "b");
+    /// djhs.link("c", "d");
+    /// djhs.link("b", "c");
+    ///
+    /// let mut replayed = DisjointHashSet::new();
+    /// replayed.replay(djhs.events().map(|event| event.map(|k| *k)));
+    /// assert!(replayed.is_linked("a", "d"));
+    /// ```
+    pub fn replay<I: IntoIterator<Item = Event<K>>>(&mut self, events: I) {
+        events.into_iter().for_each(|event| match event {
+            Event::Insert(key) => {
+                self.insert(key);
+            }
+            Event::Link(key1, key2) => self.link(key1, key2),
+        });
+    }
+}
+
+impl Forest {
+    pub(crate) fn log(&mut self, event: LoggedEvent) {
+        if let Some(events) = &mut self.events {
+            events.push(event);
+        }
+    }
+
+    /// Removes the last `count` link events from the log, keeping inserts.
+    pub(crate) fn unlog_links(&mut self, count: usize) {
+        if let Some(events) = &mut self.events {
+            let (mut start, mut links) = (events.len(), 0);
+            while links < count && start > 0 {
+                start -= 1;
+                links += matches!(events[start], LoggedEvent::Link(..)) as usize;
+            }
+
+            let tail = events.split_off(start);
+            events.extend(tail.into_iter().filter(|e| matches!(e, LoggedEvent::Insert(_))));
+            if links > 0 {
+                self.log_epoch += 1;
+            }
+        }
+    }
+}
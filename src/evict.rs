@@ -0,0 +1,230 @@
+use std::{
+    borrow::Borrow,
+    hash::{BuildHasher, Hash, RandomState},
+    mem,
+};
+
+use crate::{DisjointHashSet, KeyIndex, PointerId, forest::Forest};
+
+/// Receives every set evicted from an [`EvictingDisjointHashSet`].
+///
+/// Implemented for any `FnMut(Vec<K>)` closure.
+pub trait EvictionObserver<K> {
+    /// Called with the members of a set after it has been evicted, in
+    /// insertion order.
+    fn evicted(&mut self, members: Vec<K>);
+}
+
+impl<K, F: FnMut(Vec<K>)> EvictionObserver<K> for F {
+    fn evicted(&mut self, members: Vec<K>) {
+        self(members)
+    }
+}
+
+/// A `DisjointHashSet` holding at most a budget of keys, evicting whole sets
+/// to an [`EvictionObserver`] when it grows beyond it, least recently touched
+/// first.
+///
+/// Inserting a key, linking it, or checking whether it is linked touches its
+/// set. Once an insertion or link brings the number of keys over the budget,
+/// sets are evicted until at most three quarters of the budget remain, and
+/// always fewer keys than the budget, so that the `O(n)` rebuild that
+/// eviction takes is amortized over the insertions that follow. A set touched
+/// last is evicted too if it alone exceeds that.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::EvictingDisjointHashSet;
+///
+/// let mut evicted = Vec::new();
+/// let mut sessions = EvictingDisjointHashSet::new(4, |members| evicted.push(members));
+/// sessions.link("a1", "a2");
+/// sessions.link("b1", "b2");
+/// assert!(sessions.is_linked("a1", "a2"));
+/// sessions.insert("c1");
+/// assert_eq!(sessions.len(), 3);
+///
+/// drop(sessions);
+/// assert_eq!(evicted, vec![vec!["b1", "b2"]]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EvictingDisjointHashSet<K, O, S = RandomState> {
+    set: DisjointHashSet<K, S>,
+    /// The tick at which every set was last touched, by root.
+    touched: Vec<u64>,
+    clock: u64,
+    budget: usize,
+    observer: O,
+}
+
+impl<K: Eq + Hash, O: EvictionObserver<K>> EvictingDisjointHashSet<K, O, RandomState> {
+    /// Creates an empty `EvictingDisjointHashSet` of at most `budget` keys,
+    /// reporting evicted sets to `observer`.
+    ///
+    /// # Panics
+    /// Panics if `budget` is zero.
+    pub fn new(budget: usize, observer: O) -> Self {
+        Self::with_hasher(budget, observer, RandomState::new())
+    }
+}
+
+impl<K: Eq + Hash, O: EvictionObserver<K>, S: BuildHasher> EvictingDisjointHashSet<K, O, S> {
+    /// Creates an empty `EvictingDisjointHashSet` of at most `budget` keys,
+    /// reporting evicted sets to `observer` and using `hash_builder` to hash
+    /// the keys.
+    ///
+    /// # Panics
+    /// Panics if `budget` is zero.
+    pub fn with_hasher(budget: usize, observer: O, hash_builder: S) -> Self {
+        assert!(budget > 0, "the budget must allow at least one key");
+        Self {
+            set: DisjointHashSet::with_hasher(hash_builder),
+            touched: Vec::new(),
+            clock: 0,
+            budget,
+            observer,
+        }
+    }
+
+    /// The maximum number of keys held.
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// The number of keys held.
+    pub fn len(&self) -> usize {
+        self.set.keys.len()
+    }
+
+    /// Checks if no keys are held.
+    pub fn is_empty(&self) -> bool {
+        self.set.keys.is_empty()
+    }
+
+    /// The number of disjoint sets held.
+    pub fn set_count(&self) -> usize {
+        self.set.set_count()
+    }
+
+    /// Check if the value is held, without touching its set.
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.set.contains(val)
+    }
+
+    /// Insert the value as a new disjoint set with a single member, touching
+    /// its set. Returns true if the value was not already present. This may
+    /// evict sets, including the new one.
+    pub fn insert(&mut self, val: K) -> bool {
+        let inserted = !self.set.contains(&val);
+        let id = self.id_or_insert(val);
+        self.touch(id);
+        self.evict();
+        inserted
+    }
+
+    /// Checks if the two keys are members of the same set, touching the sets
+    /// of the keys that are present. This will not implicitly add values that
+    /// were not already present.
+    pub fn is_linked<T: Borrow<K>>(&mut self, val1: T, val2: T) -> bool {
+        let ids = (self.set.id(val1.borrow()), self.set.id(val2.borrow()));
+        ids.0.into_iter().chain(ids.1).for_each(|id| self.touch(id));
+        match ids {
+            (Some(id1), Some(id2)) => self.set.find(id1) == self.set.find(id2),
+            _ => false,
+        }
+    }
+
+    /// Link the respective sets of the two provided values, touching the
+    /// joined set. This will insert non-existent values in the process, and
+    /// may evict sets, including the joined one.
+    pub fn link(&mut self, val1: K, val2: K) {
+        let ids = (self.id_or_insert(val1), self.id_or_insert(val2));
+        self.set.link_ids(ids);
+        self.touch(ids.0);
+        self.evict();
+    }
+
+    /// Evicts every set to the observer, leaving the structure empty.
+    pub fn evict_all(&mut self) {
+        self.rebuild(|_| true);
+    }
+
+    /// Returns a reference to the observer.
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    /// Returns a mutable reference to the observer.
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
+    }
+
+    fn id_or_insert(&mut self, val: K) -> PointerId {
+        let id = self.set.id_or_insert(val);
+        self.touched.resize(self.set.keys.len(), 0);
+        id
+    }
+
+    fn touch(&mut self, id: PointerId) {
+        self.clock += 1;
+        let root = self.set.find(id);
+        self.touched[root.0] = self.clock;
+    }
+
+    /// Evicts the least recently touched sets down to the low-water mark, if
+    /// the budget is exceeded. The mark is three quarters of the budget,
+    /// rounded down, which is below the budget however small it is.
+    fn evict(&mut self) {
+        if self.len() <= self.budget {
+            return;
+        }
+
+        let mut roots: Vec<_> = self
+            .set
+            .grouped_ids()
+            .into_iter()
+            .map(|ids| (self.set.root(ids[0]), ids.len()))
+            .collect();
+        roots.sort_by_key(|&(root, _)| self.touched[root.0]);
+
+        let (mut remaining, low) = (self.len(), self.budget - self.budget.div_ceil(4));
+        let mut evicted = vec![false; self.len()];
+        for (root, size) in roots {
+            if remaining <= low {
+                break;
+            }
+            evicted[root.0] = true;
+            remaining -= size;
+        }
+        self.rebuild(|root| evicted[root.0]);
+    }
+
+    /// Rebuilds the set from the members of the sets that are kept, reporting
+    /// the members of the others to the observer.
+    fn rebuild<F: Fn(PointerId) -> bool>(&mut self, evict: F) {
+        let groups = self.set.grouped_ids();
+        let roots: Vec<PointerId> = groups.iter().map(|ids| self.set.root(ids[0])).collect();
+        let mut keys: Vec<Option<K>> =
+            mem::take(&mut self.set.keys).into_iter().map(Some).collect();
+        let touched = mem::take(&mut self.touched);
+        self.set.index = KeyIndex::default();
+        self.set.forest = Forest::with_capacity(0);
+
+        for (ids, root) in groups.into_iter().zip(roots) {
+            let mut members =
+                ids.into_iter().map(|id| keys[id.0].take().expect("ids are grouped once"));
+            if evict(root) {
+                self.observer.evicted(members.collect());
+                continue;
+            }
+
+            let first = self.set.insert_unchecked(members.next().expect("sets are not empty"));
+            members.for_each(|key| {
+                let id = self.set.insert_unchecked(key);
+                self.set.union(first, id);
+            });
+            self.touched.resize(self.set.keys.len(), 0);
+            self.touched[self.set.find(first).0] = touched[root.0];
+        }
+    }
+}
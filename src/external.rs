@@ -0,0 +1,259 @@
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::binary::{EDGE_LEN, decode};
+
+/// A pair of keys: an edge, or a key and its label.
+type Pair = (u64, u64);
+
+/// The number of sorted runs merged at once, which bounds the files open and
+/// the buffers held while merging.
+const FAN_IN: usize = 16;
+
+/// Computes the components of the binary edge stream in memory bounded by
+/// `chunk` pairs, writing the smallest key of its component for every key.
+/// Returns the number of components.
+///
+/// The edges are turned into stars around the smallest key of every component
+/// by alternating large-star and small-star rounds, each a sort of the edges
+/// by key and a pass over them.
+pub(crate) fn components<R: Read, W: Write>(
+    edges: R,
+    chunk: usize,
+    scratch: &Path,
+    labels: W,
+) -> io::Result<usize> {
+    let mut scratch = Scratch::new(scratch, chunk);
+    let edges = scratch.sort(Pairs(BufReader::new(edges)).map(|edge| edge.map(normalized)))?;
+    let keys = scratch.sort(read_pairs(&edges)?.flat_map(|edge| {
+        let keys = match edge {
+            Ok((key1, key2)) => [Some(Ok((key1, key1))), Some(Ok((key2, key2)))],
+            Err(error) => [Some(Err(error)), None],
+        };
+        keys.into_iter().flatten()
+    }))?;
+
+    let mut stars = edges;
+    loop {
+        let large = scratch.star(&stars, true)?;
+        let small = scratch.star(&large, false)?;
+        fs::remove_file(large)?;
+        let converged = same_pairs(&small, &stars)?;
+        fs::remove_file(&stars)?;
+        stars = small;
+        if converged {
+            break;
+        }
+    }
+
+    // Every key but the smallest of its component is a leaf of one star.
+    let leaves =
+        scratch.sort(read_pairs(&stars)?.map(|edge| edge.map(|(key1, key2)| (key2, key1))))?;
+    fs::remove_file(stars)?;
+    let (mut writer, mut by_leaf, mut count) = (BufWriter::new(labels), read_pairs(&leaves)?, 0);
+    let mut leaf = by_leaf.next().transpose()?;
+    for key in read_pairs(&keys)? {
+        let (key, _) = key?;
+        let label = match leaf {
+            Some((leaf_key, center)) if leaf_key == key => {
+                leaf = by_leaf.next().transpose()?;
+                center
+            }
+            _ => {
+                count += 1;
+                key
+            }
+        };
+        write_pair(&mut writer, (key, label))?;
+    }
+    writer.flush()?;
+    fs::remove_file(keys)?;
+    fs::remove_file(leaves)?;
+    Ok(count)
+}
+
+/// The scratch files of one computation, named uniquely so that concurrent
+/// computations can share a directory.
+struct Scratch<'a> {
+    dir: &'a Path,
+    prefix: String,
+    files: usize,
+    /// The number of pairs held in memory at once.
+    chunk: usize,
+}
+
+impl<'a> Scratch<'a> {
+    fn new(dir: &'a Path, chunk: usize) -> Self {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        let call = CALLS.fetch_add(1, Ordering::Relaxed);
+        Self { dir, prefix: format!("djhs-{}-{call}", process::id()), files: 0, chunk }
+    }
+
+    /// Creates a new scratch file for writing.
+    fn create(&mut self) -> io::Result<(PathBuf, BufWriter<File>)> {
+        let path = self.dir.join(format!("{}-{}.bin", self.prefix, self.files));
+        self.files += 1;
+        Ok((path.clone(), BufWriter::new(File::create(path)?)))
+    }
+
+    /// Sorts the pairs into a new file without duplicates, sorting runs of
+    /// `chunk` pairs in memory and merging them `FAN_IN` at a time.
+    fn sort<I: Iterator<Item = io::Result<Pair>>>(&mut self, pairs: I) -> io::Result<PathBuf> {
+        let (mut runs, mut run) = (Vec::new(), Vec::with_capacity(self.chunk.min(1 << 16)));
+        for pair in pairs {
+            run.push(pair?);
+            if run.len() == self.chunk {
+                runs.push(self.spill(&mut run)?);
+            }
+        }
+        if !run.is_empty() || runs.is_empty() {
+            runs.push(self.spill(&mut run)?);
+        }
+
+        while runs.len() > 1 {
+            let merging: Vec<_> = runs.drain(..runs.len().min(FAN_IN)).collect();
+            runs.push(self.merge(&merging)?);
+        }
+        Ok(runs.pop().expect("at least one run is written"))
+    }
+
+    /// Writes the run sorted and without duplicates, leaving it empty.
+    fn spill(&mut self, run: &mut Vec<Pair>) -> io::Result<PathBuf> {
+        run.sort_unstable();
+        run.dedup();
+        let (path, mut writer) = self.create()?;
+        run.drain(..).try_for_each(|pair| write_pair(&mut writer, pair))?;
+        writer.flush()?;
+        Ok(path)
+    }
+
+    /// Merges sorted runs into one without duplicates, deleting them.
+    fn merge(&mut self, runs: &[PathBuf]) -> io::Result<PathBuf> {
+        let mut readers = runs.iter().map(|run| read_pairs(run)).collect::<io::Result<Vec<_>>>()?;
+        let mut heads = BinaryHeap::new();
+        for (run, reader) in readers.iter_mut().enumerate() {
+            if let Some(pair) = reader.next().transpose()? {
+                heads.push(Reverse((pair, run)));
+            }
+        }
+
+        let (path, mut writer) = self.create()?;
+        let mut last = None;
+        while let Some(Reverse((pair, run))) = heads.pop() {
+            if last != Some(pair) {
+                write_pair(&mut writer, pair)?;
+                last = Some(pair);
+            }
+            if let Some(next) = readers[run].next().transpose()? {
+                heads.push(Reverse((next, run)));
+            }
+        }
+        writer.flush()?;
+        runs.iter().try_for_each(fs::remove_file)?;
+        Ok(path)
+    }
+
+    /// Runs a large-star or small-star round over the sorted edges, returning
+    /// the sorted edges it leaves.
+    ///
+    /// Every key is joined to the smallest key among itself and its
+    /// neighbors: a large star joins the neighbors larger than the key, and a
+    /// small star the key and its smaller neighbors.
+    fn star(&mut self, edges: &Path, large: bool) -> io::Result<PathBuf> {
+        let arcs = self.sort(read_pairs(edges)?.flat_map(|edge| {
+            let arcs = match edge {
+                Ok((key1, key2)) if key1 != key2 => {
+                    [Some(Ok((key1, key2))), Some(Ok((key2, key1)))]
+                }
+                Ok(_) => [None, None],
+                Err(error) => [Some(Err(error)), None],
+            };
+            arcs.into_iter().flatten()
+        }))?;
+
+        let (joined, mut writer) = self.create()?;
+        let (mut key, mut smallest) = (None, 0);
+        for arc in read_pairs(&arcs)? {
+            let (from, to) = arc?;
+            // The arcs of a key are sorted, so the first has its smallest neighbor.
+            if key != Some(from) {
+                key = Some(from);
+                smallest = from.min(to);
+                if !large && smallest != from {
+                    write_pair(&mut writer, (smallest, from))?;
+                }
+            }
+            let joined = if large { to > from } else { to < from && to != smallest };
+            if joined {
+                write_pair(&mut writer, (smallest, to))?;
+            }
+        }
+        writer.flush()?;
+        fs::remove_file(arcs)?;
+
+        let sorted = self.sort(read_pairs(&joined)?)?;
+        fs::remove_file(joined)?;
+        Ok(sorted)
+    }
+}
+
+/// Reads the pairs of a binary stream, failing with `UnexpectedEof` if it
+/// ends inside a pair.
+struct Pairs<R>(R);
+
+impl<R: Read> Iterator for Pairs<R> {
+    type Item = io::Result<Pair>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (mut buf, mut filled) = ([0; EDGE_LEN], 0);
+        while filled < EDGE_LEN {
+            match self.0.read(&mut buf[filled..]) {
+                Ok(0) if filled == 0 => return None,
+                Ok(0) => {
+                    let error =
+                        io::Error::new(io::ErrorKind::UnexpectedEof, "stream ends inside an edge");
+                    return Some(Err(error));
+                }
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        let (key1, key2) = buf.split_at(EDGE_LEN / 2);
+        Some(Ok((decode(key1), decode(key2))))
+    }
+}
+
+fn read_pairs(path: &Path) -> io::Result<Pairs<BufReader<File>>> {
+    Ok(Pairs(BufReader::new(File::open(path)?)))
+}
+
+fn write_pair<W: Write>(writer: &mut W, (key1, key2): Pair) -> io::Result<()> {
+    writer.write_all(&key1.to_le_bytes())?;
+    writer.write_all(&key2.to_le_bytes())
+}
+
+/// Orders the keys of an edge, smallest first.
+fn normalized((key1, key2): Pair) -> Pair {
+    (key1.min(key2), key1.max(key2))
+}
+
+/// Checks if two files of sorted pairs hold the same pairs.
+fn same_pairs(path1: &Path, path2: &Path) -> io::Result<bool> {
+    if fs::metadata(path1)?.len() != fs::metadata(path2)?.len() {
+        return Ok(false);
+    }
+    for (pair1, pair2) in read_pairs(path1)?.zip(read_pairs(path2)?) {
+        if pair1? != pair2? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
@@ -0,0 +1,122 @@
+use std::hash::{BuildHasher, Hash, RandomState};
+
+use crate::dense::DenseDisjointSet;
+
+/// Approximate connectivity over a stream of edges too large to track
+/// exactly, in memory fixed when the structure is created.
+///
+/// Rather than being stored, every key is hashed into one of a fixed number of
+/// buckets, and links join buckets. Keys that share a bucket can't be told
+/// apart, which gives the structure its error modes:
+/// - Connectivity has false positives but no false negatives. Keys that were
+///   linked are always reported linked, while two unrelated keys are reported
+///   linked if their buckets collide, directly or through the buckets of other
+///   keys.
+/// - [`set_count`](Self::set_count) never exceeds the true number of sets.
+///
+/// Collisions grow with the number of distinct keys relative to the number
+/// of buckets. The estimates stay useful while there are several buckets for
+/// every distinct key, and degrade towards a single set beyond that.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{FoldedDisjointSet, SeededState};
+///
+/// let mut sketch = FoldedDisjointSet::with_hasher(1 << 16, SeededState::new(7));
+/// sketch.link("10.0.0.1", "10.0.0.2");
+/// sketch.link("10.0.0.2", "10.0.0.3");
+/// sketch.insert("192.168.0.1");
+///
+/// // Never a false negative.
+/// assert!(sketch.is_linked("10.0.0.1", "10.0.0.3"));
+/// // Right for this seed, but not guaranteed for every seed.
+/// assert!(!sketch.is_linked("10.0.0.1", "192.168.0.1"));
+/// assert!(sketch.set_count() <= 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FoldedDisjointSet<S = RandomState> {
+    buckets: DenseDisjointSet,
+    /// Whether any key has been folded into each bucket.
+    occupied: Vec<bool>,
+    hash_builder: S,
+}
+
+impl FoldedDisjointSet<RandomState> {
+    /// Creates a `FoldedDisjointSet` that folds keys into the given number of
+    /// buckets.
+    ///
+    /// # Panics
+    /// Panics if `buckets` is zero.
+    pub fn new(buckets: usize) -> Self {
+        Self::with_hasher(buckets, RandomState::new())
+    }
+}
+
+impl<S: BuildHasher> FoldedDisjointSet<S> {
+    /// Creates a `FoldedDisjointSet` that folds keys into the given number of
+    /// buckets, using the given hash builder to hash keys, e.g. a
+    /// [`SeededState`](crate::SeededState) so that sketches of different
+    /// streams fold alike.
+    ///
+    /// # Panics
+    /// Panics if `buckets` is zero.
+    pub fn with_hasher(buckets: usize, hash_builder: S) -> Self {
+        assert!(buckets > 0, "there must be at least one bucket");
+        Self {
+            buckets: DenseDisjointSet::new(buckets),
+            occupied: vec![false; buckets],
+            hash_builder,
+        }
+    }
+
+    /// The number of buckets keys are folded into.
+    pub fn bucket_count(&self) -> usize {
+        self.occupied.len()
+    }
+
+    /// Folds the key into its bucket, so that it is counted by
+    /// [`set_count`](Self::set_count).
+    pub fn insert<K: Hash>(&mut self, key: K) {
+        self.bucket(&key);
+    }
+
+    /// Links the respective sets of the two keys, inserting them in the
+    /// process.
+    pub fn link<K: Hash>(&mut self, key1: K, key2: K) {
+        let buckets = (self.bucket(&key1), self.bucket(&key2));
+        self.buckets.union(buckets.0, buckets.1);
+    }
+
+    /// Checks if the two keys may be members of the same set. True for keys
+    /// that were linked, and possibly for keys that were not.
+    pub fn is_linked<K: Hash>(&mut self, key1: K, key2: K) -> bool {
+        let buckets = (self.index(&key1), self.index(&key2));
+        self.buckets.is_linked(buckets.0, buckets.1)
+    }
+
+    /// A lower bound on the number of disjoint sets of the inserted keys: the
+    /// number of sets of occupied buckets.
+    pub fn set_count(&mut self) -> usize {
+        let roots = self.buckets.roots();
+        let mut counted = vec![false; roots.len()];
+        roots
+            .into_iter()
+            .zip(&self.occupied)
+            .filter(|&(root, &occupied)| occupied && !std::mem::replace(&mut counted[root], true))
+            .count()
+    }
+
+    /// Returns the bucket of the key, marking it occupied.
+    fn bucket<K: Hash>(&mut self, key: &K) -> usize {
+        let bucket = self.index(key);
+        self.occupied[bucket] = true;
+        bucket
+    }
+
+    /// Maps the hash of the key onto the buckets by multiplication, which
+    /// spreads hashes evenly over any number of buckets without a division.
+    fn index<K: Hash>(&self, key: &K) -> usize {
+        let hash = self.hash_builder.hash_one(key);
+        ((hash as u128 * self.occupied.len() as u128) >> 64) as usize
+    }
+}
@@ -0,0 +1,247 @@
+use std::{collections::TryReserveError, sync::OnceLock};
+
+use crate::{
+    Checkpoint, ParentPointer, PointerId, Union, edges::RecordedEdge, events::LoggedEvent,
+    view::Components,
+};
+
+/// The parent forest over key ids, along with everything recorded about its
+/// unions.
+///
+/// Nothing here depends on the key type, so the id, parent and union logic is
+/// compiled once rather than for every `DisjointHashSet<K, S>`. The owner
+/// maps keys to ids and adds one id for every inserted key.
+#[derive(Debug, Clone)]
+pub(crate) struct Forest {
+    pub(crate) data: Vec<ParentPointer>,
+    pub(crate) history: Option<Vec<Union>>,
+    pub(crate) events: Option<Vec<LoggedEvent>>,
+    pub(crate) proofs: Option<Vec<Option<PointerId>>>,
+    pub(crate) edges: Option<Vec<RecordedEdge>>,
+    pub(crate) components: OnceLock<Components>,
+    /// The number of rollbacks, clears and released histories, the only
+    /// changes that shrink sets or invalidate checkpoints.
+    pub(crate) epoch: u64,
+    /// The number of times events already logged were discarded, which
+    /// invalidates sync points.
+    pub(crate) log_epoch: u64,
+    /// The epoch at which the union history was last discarded. Checkpoints
+    /// taken before it are invalid.
+    pub(crate) released: u64,
+    pub(crate) version: u64,
+}
+
+impl Forest {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self::from_data(Vec::with_capacity(capacity))
+    }
+
+    /// Creates a forest of the given pointers, with nothing recorded.
+    pub(crate) fn from_data(data: Vec<ParentPointer>) -> Self {
+        Self {
+            data,
+            history: None,
+            events: None,
+            proofs: None,
+            edges: None,
+            components: OnceLock::new(),
+            epoch: 0,
+            log_epoch: 0,
+            released: 0,
+            version: 0,
+        }
+    }
+
+    /// The number of ids.
+    pub(crate) fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Adds an id as a new set with a single member, returning it.
+    pub(crate) fn push(&mut self) -> PointerId {
+        let id = PointerId(self.data.len());
+        self.data.push(ParentPointer { parent: id, rank: 0, size: 1 });
+        self.log(LoggedEvent::Insert(id));
+        if let Some(proofs) = &mut self.proofs {
+            proofs.push(None);
+        }
+        self.changed();
+        id
+    }
+
+    /// Reserves room for `additional` more ids along with everything recorded
+    /// about them, failing rather than aborting if memory can't be allocated.
+    pub(crate) fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.data.try_reserve(additional)?;
+        if let Some(events) = &mut self.events {
+            events.try_reserve(additional)?;
+        }
+        if let Some(proofs) = &mut self.proofs {
+            proofs.try_reserve(additional)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_count(&self) -> usize {
+        self.data.iter().enumerate().filter(|(id, pointer)| pointer.parent.0 == *id).count()
+    }
+
+    pub(crate) fn checkpoint(&mut self) -> Checkpoint {
+        Checkpoint {
+            unions: self.history.get_or_insert_with(Vec::new).len(),
+            edges: self.edges.as_ref().map_or(0, Vec::len),
+            epoch: self.epoch,
+            version: self.version,
+        }
+    }
+
+    pub(crate) fn rollback_to(&mut self, checkpoint: Checkpoint) {
+        let history = self.history.as_mut().expect("rollback mode is not active");
+        // The union just before the checkpoint must be the one that was there
+        // when it was taken, rather than one made after an earlier rollback.
+        let intact = match checkpoint.unions {
+            0 => true,
+            unions => history.get(unions - 1).is_some_and(|u| u.version < checkpoint.version),
+        };
+        assert!(checkpoint.epoch >= self.released && intact, "checkpoint is no longer valid");
+
+        let undone = history.split_off(checkpoint.unions);
+        if let Some(edges) = &mut self.edges {
+            edges.truncate(checkpoint.edges);
+        }
+        self.unlog_links(undone.len());
+        if !undone.is_empty() {
+            self.changed();
+            self.epoch += 1;
+        }
+
+        for union in undone.into_iter().rev() {
+            let root = self.get(union.child).parent;
+            self.get_mut(union.child).parent = union.child;
+
+            self.get_mut(root).size -= self.get(union.child).size;
+            if let Some(proof) = union.proof {
+                self.unprove(proof);
+            }
+            if union.rank_increased {
+                self.get_mut(root).rank -= 1;
+            }
+        }
+    }
+
+    pub(crate) fn clear_links(&mut self) {
+        self.data.iter_mut().enumerate().for_each(|(id, pointer)| {
+            *pointer = ParentPointer { parent: PointerId(id), rank: 0, size: 1 };
+        });
+        self.release_history();
+        self.unlog_links(usize::MAX);
+        self.changed();
+        if let Some(proofs) = &mut self.proofs {
+            proofs.fill(None);
+        }
+        if let Some(edges) = &mut self.edges {
+            edges.clear();
+        }
+    }
+
+    /// Discards the union history, invalidating every checkpoint.
+    pub(crate) fn release_history(&mut self) {
+        self.history = None;
+        self.epoch += 1;
+        self.released = self.epoch;
+    }
+
+    /// Links the ids as `link` does, recording the edge if edges are
+    /// recorded. Returns true if two sets were joined.
+    pub(crate) fn link_ids(&mut self, ids: (PointerId, PointerId)) -> bool {
+        let merged = self.union(ids.0, ids.1).is_some();
+        if let Some(edges) = &mut self.edges {
+            edges.push(RecordedEdge { ids, merged });
+        }
+        merged
+    }
+
+    /// Joins the sets containing the two ids, returning the surviving root
+    /// and the root that was attached beneath it, or `None` if the ids were
+    /// already members of the same set.
+    pub(crate) fn union(
+        &mut self,
+        id1: PointerId,
+        id2: PointerId,
+    ) -> Option<(PointerId, PointerId)> {
+        let roots = (self.find(id1), self.find(id2));
+
+        if roots.0 == roots.1 {
+            return None;
+        }
+
+        let ranks = (self.get(roots.0).rank, self.get(roots.1).rank);
+        let proof = self.prove(id1, id2, (self.get(roots.0).size, self.get(roots.1).size));
+
+        let (root, child) = if ranks.0 < ranks.1 { (roots.1, roots.0) } else { (roots.0, roots.1) };
+        self.get_mut(child).parent = root;
+        self.get_mut(root).size += self.get(child).size;
+
+        let rank_increased = ranks.0 == ranks.1;
+        if rank_increased {
+            self.get_mut(root).rank += 1;
+        };
+
+        if let Some(history) = &mut self.history {
+            history.push(Union { child, rank_increased, proof, version: self.version });
+        }
+        self.log(LoggedEvent::Link(id1, id2));
+        self.changed();
+        Some((root, child))
+    }
+
+    pub(crate) fn find(&mut self, id: PointerId) -> PointerId {
+        let parent_id = self.get(id).parent;
+        let grandparent_id = self.get(parent_id).parent;
+        if parent_id == grandparent_id {
+            parent_id
+        } else if self.history.is_some() {
+            self.root(parent_id)
+        } else {
+            let root_id = self.find(parent_id);
+            self.get_mut(id).parent = root_id;
+            root_id
+        }
+    }
+
+    /// Groups the ids by set, in the order of their first ids.
+    pub(crate) fn grouped_ids(&self) -> Vec<Vec<PointerId>> {
+        let (mut slots, mut sets) = (vec![None; self.data.len()], Vec::<Vec<PointerId>>::new());
+        for id in (0..self.data.len()).map(PointerId) {
+            let slot = *slots[self.root(id).0].get_or_insert_with(|| {
+                sets.push(Vec::new());
+                sets.len() - 1
+            });
+            sets[slot].push(id);
+        }
+        sets
+    }
+
+    /// Finds the root of the id without compressing the path.
+    pub(crate) fn root(&self, mut id: PointerId) -> PointerId {
+        while self.get(id).parent != id {
+            id = self.get(id).parent;
+        }
+        id
+    }
+
+    /// Discards anything derived from the partition, after an insertion or a
+    /// change to its sets.
+    pub(crate) fn changed(&mut self) {
+        self.components.take();
+        self.version += 1;
+    }
+
+    pub(crate) fn get(&self, id: PointerId) -> &ParentPointer {
+        &self.data[id.0]
+    }
+
+    pub(crate) fn get_mut(&mut self, id: PointerId) -> &mut ParentPointer {
+        &mut self.data[id.0]
+    }
+}
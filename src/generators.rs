@@ -0,0 +1,125 @@
+//! Random workloads for benchmarking and stress-testing code built on
+//! [`DisjointHashSet`](crate::DisjointHashSet).
+//!
+//! Generators take a `random_below(n)` closure that must return a uniformly
+//! random number below `n`, e.g. from the caller's own random number
+//! generator, so that workloads are reproducible for a seeded generator.
+
+/// The distribution of the sizes of the sets formed by [`random_links`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeDistribution {
+    /// Every size from `1` to `max` is equally likely.
+    Uniform {
+        /// The largest size.
+        max: usize,
+    },
+    /// A size `s` from `1` to `max` is drawn with a probability proportional
+    /// to `s^-exponent`, giving many small sets and a few large ones.
+    PowerLaw {
+        /// The exponent of the distribution, typically between `1` and `3`.
+        exponent: f64,
+        /// The largest size.
+        max: usize,
+    },
+}
+
+/// Generates a random sequence of links between the keys `0..keys` that
+/// partitions them into sets with sizes drawn from the distribution. The
+/// last set is cut short to fit the remaining keys.
+///
+/// Every set is joined by a random spanning tree, so that every link is an
+/// effective union, and the links of all sets are shuffled together.
+///
+/// # Panics
+/// Panics if the largest size of the distribution is `0`.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{
+///     DisjointHashSet,
+///     generators::{SizeDistribution, random_links},
+/// };
+///
+/// let mut state = 0x2545_f491_u64;
+/// let random_below = |n: usize| {
+///     state ^= state << 13;
+///     state ^= state >> 7;
+///     state ^= state << 17;
+///     (state % n as u64) as usize
+/// };
+///
+/// let sizes = SizeDistribution::PowerLaw { exponent: 2.0, max: 1_000 };
+/// let links = random_links(10_000, sizes, random_below);
+///
+/// let mut djhs = DisjointHashSet::new();
+/// (0..10_000).for_each(|key| {
+///     djhs.insert(key);
+/// });
+/// let summary = djhs.link_all(links);
+/// assert_eq!(summary.redundant, 0);
+/// assert_eq!(djhs.set_count(), 10_000 - summary.merged);
+/// ```
+pub fn random_links<R>(
+    keys: usize,
+    sizes: SizeDistribution,
+    mut random_below: R,
+) -> Vec<(usize, usize)>
+where
+    R: FnMut(usize) -> usize,
+{
+    let mut draw_size = size_sampler(sizes);
+    let mut order: Vec<usize> = (0..keys).collect();
+    shuffle(&mut order, &mut random_below);
+
+    let mut links = Vec::with_capacity(keys);
+    let mut rest = &order[..];
+    while !rest.is_empty() {
+        let size = draw_size(&mut random_below).min(rest.len());
+        let (set, tail) = rest.split_at(size);
+        for member in 1..set.len() {
+            links.push((set[member], set[random_below(member)]));
+        }
+        rest = tail;
+    }
+
+    shuffle(&mut links, &mut random_below);
+    links
+}
+
+/// Returns a closure drawing sizes from the distribution.
+fn size_sampler<R>(sizes: SizeDistribution) -> impl FnMut(&mut R) -> usize
+where
+    R: FnMut(usize) -> usize,
+{
+    let (max, cumulative) = match sizes {
+        SizeDistribution::Uniform { max } => (max, None),
+        SizeDistribution::PowerLaw { exponent, max } => {
+            let weights = (1..=max).map(|size| (size as f64).powf(-exponent));
+            let cumulative: Vec<f64> = weights
+                .scan(0.0, |total, weight| {
+                    *total += weight;
+                    Some(*total)
+                })
+                .collect();
+            (max, Some(cumulative))
+        }
+    };
+    assert!(max > 0, "the largest set size must be positive");
+
+    move |random_below: &mut R| match &cumulative {
+        None => 1 + random_below(max),
+        Some(cumulative) => {
+            const RESOLUTION: usize = 1 << 24;
+            let total = cumulative[max - 1];
+            let target = random_below(RESOLUTION) as f64 / RESOLUTION as f64 * total;
+            1 + cumulative.partition_point(|&sum| sum <= target).min(max - 1)
+        }
+    }
+}
+
+/// Shuffles the items with the Fisher–Yates shuffle.
+pub(crate) fn shuffle<T, R: FnMut(usize) -> usize>(items: &mut [T], random_below: &mut R) {
+    for i in (1..items.len()).rev() {
+        items.swap(i, random_below(i + 1));
+    }
+}
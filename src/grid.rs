@@ -0,0 +1,96 @@
+use std::hash::BuildHasher;
+
+use crate::DisjointHashSet;
+
+/// The neighbourhood of a cell of a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    /// The cells sharing an edge with the cell.
+    Four,
+    /// The cells sharing an edge or a corner with the cell.
+    Eight,
+}
+
+impl Connectivity {
+    /// The neighbours of the cell, skipping those with negative coordinates.
+    pub(crate) fn neighbors(self, (x, y): (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        const FOUR: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+        const CORNERS: [(isize, isize); 4] = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+
+        let corners = match self {
+            Connectivity::Four => &CORNERS[..0],
+            Connectivity::Eight => &CORNERS[..],
+        };
+        FOUR.iter().chain(corners).filter_map(move |&(dx, dy)| {
+            Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?))
+        })
+    }
+}
+
+impl<S: BuildHasher> DisjointHashSet<(usize, usize), S> {
+    /// Inserts the cell, linking it to every neighbouring cell already present.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{Connectivity, DisjointHashSet};
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link_grid_neighbors((0, 0), Connectivity::Four);
+    /// djhs.link_grid_neighbors((1, 1), Connectivity::Four);
+    /// assert!(!djhs.is_linked((0, 0), (1, 1)));
+    ///
+    /// djhs.link_grid_neighbors((2, 2), Connectivity::Eight);
+    /// djhs.link_grid_neighbors((1, 0), Connectivity::Four);
+    /// assert!(djhs.is_linked((0, 0), (2, 2)));
+    /// ```
+    pub fn link_grid_neighbors(&mut self, cell: (usize, usize), connectivity: Connectivity) {
+        self.insert(cell);
+        for neighbor in connectivity.neighbors(cell) {
+            if self.contains(neighbor) {
+                self.link(cell, neighbor);
+            }
+        }
+    }
+
+    /// Inserts every cell of a `width` by `height` grid that satisfies the
+    /// predicate, linking it to its neighbouring cells that satisfy it too.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{Connectivity, DisjointHashSet};
+    ///
+    /// let image = ["#..#", "#..#", "....", ".##."];
+    /// let filled = |(x, y): (usize, usize)| image[y].as_bytes()[x] == b'#';
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link_grid_where(4, 4, Connectivity::Four, filled);
+    /// assert_eq!(djhs.set_count(), 3);
+    /// assert!(djhs.is_linked((0, 0), (0, 1)));
+    /// assert!(!djhs.contains((1, 1)));
+    /// ```
+    pub fn link_grid_where<F>(
+        &mut self,
+        width: usize,
+        height: usize,
+        connectivity: Connectivity,
+        mut predicate: F,
+    ) where
+        F: FnMut((usize, usize)) -> bool,
+    {
+        let mut selected = vec![false; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                if !predicate((x, y)) {
+                    continue;
+                }
+                selected[y * width + x] = true;
+                self.insert((x, y));
+
+                // Neighbours later in the scan link to this cell in turn.
+                for (nx, ny) in connectivity.neighbors((x, y)) {
+                    if (ny, nx) < (y, x) && nx < width && selected[ny * width + nx] {
+                        self.link((x, y), (nx, ny));
+                    }
+                }
+            }
+        }
+    }
+}
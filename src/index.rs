@@ -0,0 +1,115 @@
+use std::{
+    collections::{HashMap, TryReserveError},
+    hash::{BuildHasherDefault, Hasher},
+};
+
+use crate::{PointerId, bloom::BloomFilter};
+
+/// Maps key hashes to the ids of the keys with that hash.
+///
+/// Keys themselves are stored by the owner in id order. The index only holds
+/// the first id for every hash, with any further ids chained through `next`,
+/// so that keys can be looked up by hash without being stored twice.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct KeyIndex {
+    heads: HashMap<u64, PointerId, BuildHasherDefault<HashKeyHasher>>,
+    next: Vec<Option<PointerId>>,
+    /// A filter of the hashes in `heads`, if enabled.
+    bloom: Option<BloomFilter>,
+}
+
+impl KeyIndex {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            heads: HashMap::with_capacity_and_hasher(capacity, Default::default()),
+            next: Vec::with_capacity(capacity),
+            bloom: None,
+        }
+    }
+
+    /// Reserves room for at least `additional` more ids.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.heads.reserve(additional);
+        self.next.reserve(additional);
+    }
+
+    /// Reserves room for `additional` more ids, failing rather than aborting
+    /// if memory can't be allocated.
+    pub(crate) fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.heads.try_reserve(additional)?;
+        self.next.try_reserve(additional)?;
+        // Grow the Bloom filter now, as the insertions would have.
+        if self.bloom.as_ref().is_some_and(|bloom| !bloom.has_room(additional)) {
+            let capacity = (self.heads.len() + additional) * 2;
+            let mut bloom = BloomFilter::try_with_capacity(capacity)?;
+            self.heads.keys().for_each(|&hash| bloom.insert(hash));
+            self.bloom = Some(bloom);
+        }
+        Ok(())
+    }
+
+    /// Starts filtering lookups by a Bloom filter of the hashes.
+    pub(crate) fn enable_bloom_filter(&mut self) {
+        if self.bloom.is_none() {
+            self.rebuild_bloom_filter(self.heads.len() * 2);
+        }
+    }
+
+    pub(crate) fn disable_bloom_filter(&mut self) {
+        self.bloom = None;
+    }
+
+    /// Returns the first id with the given hash for which `eq` holds.
+    pub(crate) fn get(&self, hash: u64, eq: impl Fn(PointerId) -> bool) -> Option<PointerId> {
+        if self.bloom.as_ref().is_some_and(|bloom| !bloom.may_contain(hash)) {
+            return None;
+        }
+        let mut id = self.heads.get(&hash).copied();
+        while let Some(candidate) = id {
+            if eq(candidate) {
+                return Some(candidate);
+            }
+            id = self.next[candidate.0];
+        }
+        None
+    }
+
+    /// Indexes the next id under the given hash. Ids must be inserted in
+    /// order, starting from zero.
+    pub(crate) fn insert(&mut self, hash: u64, id: PointerId) {
+        debug_assert_eq!(id.0, self.next.len());
+        self.next.push(self.heads.insert(hash, id));
+        match &mut self.bloom {
+            Some(bloom) if bloom.is_full() => self.rebuild_bloom_filter(self.heads.len() * 2),
+            Some(bloom) => bloom.insert(hash),
+            None => {}
+        }
+    }
+
+    /// Replaces the Bloom filter by one sized for `capacity` hashes holding
+    /// those in `heads`.
+    fn rebuild_bloom_filter(&mut self, capacity: usize) {
+        let mut bloom = BloomFilter::with_capacity(capacity);
+        self.heads.keys().for_each(|&hash| bloom.insert(hash));
+        self.bloom = Some(bloom);
+    }
+}
+
+/// A `Hasher` for keys that are already hashes, spreading their bits rather
+/// than hashing them again.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct HashKeyHasher(u64);
+
+impl Hasher for HashKeyHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        bytes.iter().for_each(|&b| self.write_u64(b as u64));
+    }
+
+    fn write_u64(&mut self, hash: u64) {
+        self.0 = (self.0 ^ hash).wrapping_mul(0x9e37_79b9_7f4a_7c15).rotate_left(26);
+    }
+}
@@ -0,0 +1,145 @@
+use std::ops::RangeInclusive;
+
+use crate::SuccessorDisjointSet;
+
+/// A partition of the positions `0..len` into contiguous intervals, grown by
+/// linking whole ranges at once.
+///
+/// Linking a range joins every position in it, along with the intervals they
+/// already belonged to. Every pair of neighbouring positions is joined at most
+/// once, and position coverage is tracked the same way, so any sequence of
+/// [`link_range`](Self::link_range) calls costs amortized near-constant time
+/// per position rather than per position of every range.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::IntervalDisjointSet;
+///
+/// // Merge overlapping reads on a 20 base region.
+/// let mut region = IntervalDisjointSet::new(20);
+/// region.link_range(2..=6);
+/// region.link_range(5..=9);
+/// region.link_range(14..=16);
+///
+/// assert_eq!(region.interval(3), 2..=9);
+/// assert!(region.is_linked(2, 9));
+/// assert!(!region.is_linked(9, 14));
+/// assert_eq!(region.next_uncovered(2), Some(10));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalDisjointSet {
+    /// Slot `i` is erased once positions `i` and `i + 1` are joined.
+    joins: SuccessorDisjointSet,
+    /// `joins` mirrored, so that the start of an interval can be found.
+    mirrored: SuccessorDisjointSet,
+    /// Slot `i` is erased once position `i` is covered by a linked range.
+    uncovered: SuccessorDisjointSet,
+}
+
+impl IntervalDisjointSet {
+    /// Creates `len` positions, each an interval of its own.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::IntervalDisjointSet;
+    /// let mut region = IntervalDisjointSet::new(1_000);
+    /// assert_eq!(region.interval(500), 500..=500);
+    /// ```
+    pub fn new(len: usize) -> Self {
+        let joins = len.saturating_sub(1);
+        Self {
+            joins: SuccessorDisjointSet::new(joins),
+            mirrored: SuccessorDisjointSet::new(joins),
+            uncovered: SuccessorDisjointSet::new(len),
+        }
+    }
+
+    /// The number of positions.
+    pub fn len(&self) -> usize {
+        self.uncovered.len()
+    }
+
+    /// Checks if there are no positions at all.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Links every position in the range, marking them covered. An empty
+    /// range does nothing.
+    ///
+    /// # Panics
+    /// Panics if the range ends out of bounds.
+    pub fn link_range(&mut self, range: RangeInclusive<usize>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        assert!(end < self.len(), "range end {end} out of range for {} positions", self.len());
+
+        let mirror = self.joins.len().saturating_sub(1);
+        let mut join = self.joins.find_next_free(start);
+        while let Some(i) = join.filter(|&i| i < end) {
+            self.joins.erase(i);
+            self.mirrored.erase(mirror - i);
+            join = self.joins.find_next_free(i + 1);
+        }
+
+        let mut position = self.uncovered.find_next_free(start);
+        while let Some(i) = position.filter(|&i| i <= end) {
+            self.uncovered.erase(i);
+            position = self.uncovered.find_next_free(i + 1);
+        }
+    }
+
+    /// Checks if the two positions are members of the same interval.
+    ///
+    /// # Panics
+    /// Panics if either position is out of range.
+    pub fn is_linked(&mut self, position1: usize, position2: usize) -> bool {
+        let (from, to) = (position1.min(position2), position1.max(position2));
+        assert!(to < self.len(), "position {to} out of range for {} positions", self.len());
+        self.joins.find_next_free(from).is_none_or(|i| i >= to)
+    }
+
+    /// Returns the interval containing the position.
+    ///
+    /// # Panics
+    /// Panics if the position is out of range.
+    pub fn interval(&mut self, position: usize) -> RangeInclusive<usize> {
+        assert!(
+            position < self.len(),
+            "position {position} out of range for {} positions",
+            self.len()
+        );
+
+        let joins = self.joins.len();
+        let start = match self.mirrored.find_next_free(joins - position) {
+            Some(mirrored) => joins - mirrored,
+            None => 0,
+        };
+        let end = self.joins.find_next_free(position).unwrap_or(joins);
+        start..=end
+    }
+
+    /// Checks if the position has been covered by a linked range.
+    pub fn is_covered(&self, position: usize) -> bool {
+        position < self.len() && !self.uncovered.is_free(position)
+    }
+
+    /// Returns the first position at or after `position` that no linked range
+    /// has covered, or `None` if there is none.
+    ///
+    /// ```
+    /// use disjoint_hash_set::IntervalDisjointSet;
+    ///
+    /// let mut region = IntervalDisjointSet::new(6);
+    /// region.link_range(0..=2);
+    /// region.link_range(3..=3);
+    /// assert_eq!(region.next_uncovered(0), Some(4));
+    /// region.link_range(4..=5);
+    /// assert_eq!(region.next_uncovered(0), None);
+    /// ```
+    pub fn next_uncovered(&mut self, position: usize) -> Option<usize> {
+        self.uncovered.find_next_free(position)
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    fmt::Display,
+    hash::{BuildHasher, Hash},
+    io::{self, Write},
+};
+
+use crate::DisjointHashSet;
+
+impl<K: Eq + Hash + Display, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Renders the sets as a JSON array of groups, each with the first key of
+    /// the set as its representative and every key as its members, in
+    /// insertion order. Keys are written as JSON strings of their `Display`
+    /// output.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "c")]);
+    /// assert_eq!(
+    ///     djhs.to_json_groups(),
+    ///     r#"[{"representative":"a","members":["a","b"]},{"representative":"c","members":["c"]}]"#
+    /// );
+    /// ```
+    pub fn to_json_groups(&self) -> String {
+        let mut json = Vec::new();
+        self.write_json_groups(&mut json).expect("writing to a Vec can't fail");
+        String::from_utf8(json).expect("JSON output is UTF-8")
+    }
+
+    /// Writes the sets as a JSON array of groups to the writer, as by
+    /// [`to_json_groups`](Self::to_json_groups).
+    pub fn write_json_groups<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(b"[")?;
+        for (index, set) in self.grouped_ids().into_iter().enumerate() {
+            if index > 0 {
+                writer.write_all(b",")?;
+            }
+            writer.write_all(b"{\"representative\":")?;
+            write_string(&mut writer, self.key(set[0]))?;
+            writer.write_all(b",\"members\":[")?;
+            for (index, &id) in set.iter().enumerate() {
+                if index > 0 {
+                    writer.write_all(b",")?;
+                }
+                write_string(&mut writer, self.key(id))?;
+            }
+            writer.write_all(b"]}")?;
+        }
+        writer.write_all(b"]")
+    }
+}
+
+/// Writes the `Display` output of the key as a JSON string.
+fn write_string<W: Write, K: Display>(writer: &mut W, key: &K) -> io::Result<()> {
+    let mut string = String::from("\"");
+    for c in key.to_string().chars() {
+        match c {
+            '"' => string.push_str("\\\""),
+            '\\' => string.push_str("\\\\"),
+            '\n' => string.push_str("\\n"),
+            '\r' => string.push_str("\\r"),
+            '\t' => string.push_str("\\t"),
+            c if c < ' ' => string.push_str(&format!("\\u{:04x}", c as u32)),
+            c => string.push(c),
+        }
+    }
+    string.push('"');
+    writer.write_all(string.as_bytes())
+}
@@ -27,17 +27,159 @@
 //! let sets = djhs.sets(); // looks like [{"a", "b", "c"}, {"d", "e"}, {"f"}]
 //! assert_eq!(sets.count(), 3);
 //! ```
+//!
+//! Per-set values such as sizes, sums, or extrema can be maintained
+//! incrementally with an [`AggregatedDisjointHashSet`], and a
+//! [`PersistentDisjointHashSet`] offers `O(1)` snapshots that share storage.
+//! A [`VersionedDisjointHashSet`] answers connectivity queries for past
+//! versions of the partition, and a [`TimestampedDisjointHashSet`] reports
+//! when two keys first became connected. An [`ObservedDisjointHashSet`]
+//! reports every effective union to a [`MergeObserver`], and a
+//! [`ConstrainedDisjointHashSet`] refuses links that violate cannot-link
+//! constraints. A [`WeightedDisjointHashSet`] tracks relations such as
+//! differences between linked keys, and a [`ParityDisjointHashSet`] whether
+//! they are equal or unequal. A [`DynamicDisjointHashSet`] also allows links
+//! to be removed again. Over a plain range of indices, a
+//! [`SuccessorDisjointSet`] finds the next slot that has not been erased, and
+//! an [`IntervalDisjointSet`] links whole ranges of positions at once. Keys
+//! that are `Ord` but not `Hash` can be partitioned with a
+//! [`DisjointBTreeSet`], and a [`SharedDisjointHashSet`] can be linked and
+//! queried from several threads at once. A [`WalDisjointHashSet`] logs every
+//! change, so that the partition can be recovered after a crash. For streams
+//! too large to track exactly, a [`FoldedDisjointSet`] approximates
+//! connectivity in fixed memory, and an [`EvictingDisjointHashSet`] holds at
+//! most a budget of keys, evicting the least recently touched sets. A
+//! [`NamespacedDisjointHashSet`] keeps several named partitions over one
+//! table of keys, and a [`BipartiteDisjointHashSet`] links keys of two
+//! different types.
+//!
+//! The [`algorithms`] module builds classic graph algorithms, such as
+//! minimum spanning forests, on top of these structures.
+//!
+//! ## Features
+//! - `analysis`: program analyses in the `analysis` module, such as
+//!   Steensgaard's points-to analysis.
+//! - `csv`: reading edge lists from and writing key labels to CSV files, such
+//!   as with `DisjointHashSet::read_edges_csv`.
+//! - `lsh`: a `BandIndex` linking near-duplicate documents that share a band
+//!   hash of a MinHash / LSH pipeline.
+//! - `metrics`: similarity measures between two partitions, such as
+//!   `DisjointHashSet::rand_index`.
 
 use std::{
     borrow::Borrow,
     collections::{HashMap, HashSet},
     hash::{BuildHasher, Hash, RandomState},
+    iter::FusedIterator,
 };
 
+mod aggregate;
+pub mod algorithms;
+#[cfg(feature = "analysis")]
+pub mod analysis;
+mod binary;
+mod bipartite;
+mod bloom;
+mod borrowed;
+mod canonical;
+mod congruence;
+mod constraint;
+#[cfg(feature = "csv")]
+mod csv;
+mod dense;
+mod deterministic;
+mod dot;
+mod dynamic;
+mod edges;
+mod error;
+mod events;
+mod evict;
+mod external;
+mod folded;
+mod forest;
+pub mod generators;
+mod grid;
+mod index;
+mod interval;
+mod json;
+#[cfg(feature = "lsh")]
+mod lsh;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod namespaced;
+mod observe;
+mod offline;
+mod ordered;
+mod parity;
+mod partition;
+mod parts;
+mod percolation;
+mod persist;
+mod persistent;
+mod prehashed;
+mod proof;
+mod query;
+mod sample;
+mod shared;
+mod successor;
+mod sync;
+mod unify;
+mod versioned;
+mod view;
+mod wal;
+mod watch;
+mod weighted;
+
+use forest::Forest;
+use index::KeyIndex;
+
+pub use aggregate::{Aggregate, AggregatedDisjointHashSet, Count, Fold};
+pub use algorithms::connected_components;
+pub use bipartite::BipartiteDisjointHashSet;
+pub use congruence::{CongruenceClosure, TermId};
+pub use constraint::{ConstrainedDisjointHashSet, ConstraintViolation, Violation};
+#[cfg(feature = "csv")]
+pub use csv::CsvOptions;
+pub use deterministic::{SeededHasher, SeededState};
+pub use dot::DotView;
+pub use dynamic::DynamicDisjointHashSet;
+pub use error::Error;
+pub use events::Event;
+pub use evict::{EvictingDisjointHashSet, EvictionObserver};
+pub use folded::FoldedDisjointSet;
+pub use grid::Connectivity;
+pub use interval::IntervalDisjointSet;
+#[cfg(feature = "lsh")]
+pub use lsh::BandIndex;
+pub use namespaced::NamespacedDisjointHashSet;
+pub use observe::{Merge, MergeObserver, ObservedDisjointHashSet};
+pub use offline::Operation;
+pub use ordered::DisjointBTreeSet;
+pub use parity::ParityDisjointHashSet;
+pub use partition::{EdgeCounts, PartitionDiff};
+pub use parts::{IntegrityError, Parts};
+pub use percolation::Percolation;
+pub use persist::BinaryKey;
+pub use persistent::PersistentDisjointHashSet;
+pub use prehashed::{PreHashed, PreHashedHasher, PreHashedState};
+pub use query::DisjointQuery;
+pub use shared::SharedDisjointHashSet;
+pub use successor::SuccessorDisjointSet;
+pub use sync::{Delta, SyncPoint};
+pub use unify::{Term, Unifier, UnifyError};
+pub use versioned::{TimestampedDisjointHashSet, VersionedDisjointHashSet};
+pub use view::ComponentsView;
+pub use wal::WalDisjointHashSet;
+pub use watch::WatchToken;
+pub use weighted::{Contradiction, Group, WeightedDisjointHashSet};
+
 #[derive(Debug, Clone)]
 pub struct DisjointHashSet<K, S = RandomState> {
-    ids: HashMap<K, PointerId, S>,
-    data: Vec<ParentPointer>,
+    keys: Vec<K>,
+    index: KeyIndex,
+    hash_builder: S,
+    /// Everything about the partition that doesn't depend on the key type.
+    forest: Forest,
 }
 
 impl<K: Eq + Hash> DisjointHashSet<K, RandomState> {
@@ -49,7 +191,7 @@ impl<K: Eq + Hash> DisjointHashSet<K, RandomState> {
     /// let mut djhs: DisjointHashSet<&str> = DisjointHashSet::new();
     /// ```
     pub fn new() -> Self {
-        Self { ids: HashMap::new(), data: Vec::new() }
+        Self::with_capacity_and_hasher(0, RandomState::new())
     }
 
     /// Creates an empty `DisjointHashSet` with at least the specified capacity.
@@ -60,7 +202,83 @@ impl<K: Eq + Hash> DisjointHashSet<K, RandomState> {
     /// let mut djhs: DisjointHashSet<&str> = DisjointHashSet::with_capacity(10);
     /// ```
     pub fn with_capacity(capacity: usize) -> Self {
-        Self { ids: HashMap::with_capacity(capacity), data: Vec::with_capacity(capacity) }
+        Self::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+
+    /// Builds a `DisjointHashSet` from a large collection of edges.
+    ///
+    /// Unlike collecting edges one `link` at a time, the keys are first
+    /// assigned ids with capacity reserved up front, duplicate and reversed
+    /// edges are removed, and the remaining unions are applied in id order to
+    /// keep memory access local. The resulting partition is identical.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let edges = vec![("a", "b"), ("b", "a"), ("a", "b"), ("c", "d"), ("e", "e")];
+    /// let mut djhs = DisjointHashSet::from_edges_bulk(edges);
+    /// assert!(djhs.is_linked("a", "b"));
+    /// assert!(djhs.is_linked("d", "c"));
+    /// assert!(djhs.contains("e"));
+    /// assert_eq!(djhs.sets().count(), 3);
+    /// ```
+    pub fn from_edges_bulk<I: IntoIterator<Item = (K, K)>>(edges: I) -> Self {
+        let edges = edges.into_iter();
+        let mut djhs = Self::with_capacity(edges.size_hint().0);
+
+        let mut pairs: Vec<(PointerId, PointerId)> = edges
+            .map(|(a, b)| {
+                let ids = (djhs.id_or_insert(a), djhs.id_or_insert(b));
+                (ids.0.min(ids.1), ids.0.max(ids.1))
+            })
+            .filter(|(a, b)| a != b)
+            .collect();
+
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs.into_iter().for_each(|(a, b)| {
+            djhs.union(a, b);
+        });
+        djhs
+    }
+
+    /// Builds a `DisjointHashSet` of the keys, linking those that share the
+    /// same projection by `f`, without materializing pairs of linked keys.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let emails = ["Ann@Example.com", "bob@example.com", "ann@example.com", "BOB@example.com"];
+    /// let mut djhs = DisjointHashSet::partition_by(emails, |email| email.to_lowercase());
+    /// assert!(djhs.is_linked("Ann@Example.com", "ann@example.com"));
+    /// assert!(!djhs.is_linked("ann@example.com", "bob@example.com"));
+    /// assert_eq!(djhs.set_count(), 2);
+    /// ```
+    pub fn partition_by<I, F, P>(keys: I, mut f: F) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        F: FnMut(&K) -> P,
+        P: Eq + Hash,
+    {
+        let keys = keys.into_iter();
+        let mut djhs = Self::with_capacity(keys.size_hint().0);
+
+        let mut classes = HashMap::new();
+        for key in keys {
+            let projection = f(&key);
+            let id = djhs.id_or_insert(key);
+            let class = *classes.entry(projection).or_insert(id);
+            djhs.union(class, id);
+        }
+        djhs
+    }
+}
+
+impl<K: Eq + Hash> Default for DisjointHashSet<K, RandomState> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -77,7 +295,7 @@ impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
     /// let mut djhs: DisjointHashSet<&str> = DisjointHashSet::with_hasher(s);
     /// ```
     pub fn with_hasher(hash_builder: S) -> Self {
-        Self { ids: HashMap::with_hasher(hash_builder), data: Vec::new() }
+        Self::with_capacity_and_hasher(0, hash_builder)
     }
 
     /// Creates an empty `DisjoingHashSet` with at least the specified capacity,
@@ -93,8 +311,10 @@ impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
     /// ```
     pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
         Self {
-            ids: HashMap::with_capacity_and_hasher(capacity, hasher),
-            data: Vec::with_capacity(capacity),
+            keys: Vec::with_capacity(capacity),
+            index: KeyIndex::with_capacity(capacity),
+            hash_builder: hasher,
+            forest: Forest::with_capacity(capacity),
         }
     }
 
@@ -113,6 +333,49 @@ impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
         self.id(val.borrow()).is_some()
     }
 
+    /// Checks if every one of the values has already been inserted, e.g. to
+    /// validate a batch of edges before linking it with
+    /// [`try_link`](Self::try_link). True if there are no values.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![("a", "b")]);
+    /// assert!(djhs.contains_all(["a", "b"]));
+    /// assert!(!djhs.contains_all(["a", "c"]));
+    /// ```
+    pub fn contains_all<T: Borrow<K>, I: IntoIterator<Item = T>>(&self, vals: I) -> bool {
+        self.first_missing(vals).is_none()
+    }
+
+    /// Checks if any of the values has already been inserted. False if there
+    /// are no values.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![("a", "b")]);
+    /// assert!(djhs.contains_any(["c", "b"]));
+    /// assert!(!djhs.contains_any(["c", "d"]));
+    /// ```
+    pub fn contains_any<T: Borrow<K>, I: IntoIterator<Item = T>>(&self, vals: I) -> bool {
+        vals.into_iter().any(|val| self.contains(val))
+    }
+
+    /// Returns the first of the values that has not been inserted, or `None`
+    /// if all of them are present.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![("a", "b")]);
+    /// assert_eq!(djhs.first_missing(["a", "c", "d"]), Some("c"));
+    /// assert_eq!(djhs.first_missing(["b", "a"]), None);
+    /// ```
+    pub fn first_missing<T: Borrow<K>, I: IntoIterator<Item = T>>(&self, vals: I) -> Option<T> {
+        vals.into_iter().find(|val| !self.contains(val.borrow()))
+    }
+
     /// Insert the value as a new disjoint set with a single member. Returns
     /// true if the value was not already present.
     ///
@@ -127,6 +390,29 @@ impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
         (!self.contains(&val)).then(|| self.insert_unchecked(val)).is_some()
     }
 
+    /// Inserts every value as a new disjoint set with a single member, as by
+    /// [`insert`](Self::insert), and returns how many of them were not
+    /// already present. Room for the values is reserved up front, by the
+    /// lower bound of the iterator's size hint.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.insert(0);
+    /// assert_eq!(djhs.insert_all(0..1000), 999);
+    /// assert_eq!(djhs.insert_all([0, 1000, 1000]), 1);
+    /// assert_eq!(djhs.set_count(), 1001);
+    /// ```
+    pub fn insert_all<I: IntoIterator<Item = K>>(&mut self, vals: I) -> usize {
+        let vals = vals.into_iter();
+        let additional = vals.size_hint().0;
+        self.keys.reserve(additional);
+        self.index.reserve(additional);
+        self.forest.data.reserve(additional);
+        vals.map(|val| self.insert(val)).filter(|&new| new).count()
+    }
+
     /// Checks if the two keys are members of the same set.
     /// This will not implicitly add values that were not already present.
     /// ```
@@ -147,8 +433,64 @@ impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
         id1.is_some() && id2.is_some() && id1 == id2
     }
 
+    /// Returns the representative of the value's set, a member shared by every
+    /// key of the set, or `None` if the value is not present. Which member
+    /// represents a set may change when it is joined with another.
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link("a", "b");
+    /// assert_eq!(djhs.representative("a"), djhs.representative("b"));
+    /// assert_eq!(djhs.representative("c"), None);
+    /// ```
+    pub fn representative<T: Borrow<K>>(&self, val: T) -> Option<&K> {
+        self.id(val.borrow()).map(|id| self.key(self.root(id)))
+    }
+
+    /// The number of members of the value's set, or `None` if the value is not
+    /// present.
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("b", "c"), ("d", "d")]);
+    /// assert_eq!(djhs.set_size("c"), Some(3));
+    /// assert_eq!(djhs.set_size("d"), Some(1));
+    /// assert_eq!(djhs.set_size("e"), None);
+    /// ```
+    pub fn set_size<T: Borrow<K>>(&self, val: T) -> Option<usize> {
+        self.id(val.borrow()).map(|id| self.get(self.root(id)).size)
+    }
+
+    /// Returns an iterator over the chain of parents from the value to the
+    /// representative of its set, both included, as currently stored. Unlike
+    /// [`is_linked`](Self::is_linked), this does not compress the path. The
+    /// iterator is empty if the value is not present.
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link("a", "b");
+    /// djhs.link("c", "d");
+    /// djhs.link("a", "c");
+    ///
+    /// assert_eq!(djhs.find_path("d").collect::<Vec<_>>(), vec![&"d", &"c", &"a"]);
+    /// assert!(djhs.is_linked("d", "a"));
+    /// assert_eq!(djhs.find_path("d").collect::<Vec<_>>(), vec![&"d", &"a"]);
+    /// assert_eq!(djhs.find_path("e").count(), 0);
+    /// ```
+    pub fn find_path<T: Borrow<K>>(&self, val: T) -> impl Iterator<Item = &K> {
+        let start = self.id(val.borrow());
+        std::iter::successors(start, |&id| Some(self.get(id).parent).filter(|&parent| parent != id))
+            .map(|id| self.key(id))
+    }
+
     /// Link the respective sets of the two provided values. This will insert
     /// non-existent values in the process.
+    ///
+    /// When the sets are of equal rank, the set of `val1` absorbs the other,
+    /// so representatives depend only on the order of insertions and links,
+    /// never on the hasher.
     /// ```
     /// use disjoint_hash_set::DisjointHashSet;
     ///
@@ -160,33 +502,236 @@ impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
     /// assert!(djhs.is_linked("a", "b"));
     /// ```
     pub fn link(&mut self, val1: K, val2: K) {
+        let ids = (self.id_or_insert(val1), self.id_or_insert(val2));
+        self.link_ids(ids);
+    }
+
+    /// Links the respective sets of the two provided values as by
+    /// [`link`](Self::link), returning the representative of the joined set.
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link("a", "b");
+    /// assert_eq!(djhs.link_get("c", "b"), &"a");
+    /// assert_eq!(djhs.representative("c"), Some(&"a"));
+    /// ```
+    pub fn link_get(&mut self, val1: K, val2: K) -> &K {
+        let ids = (self.id_or_insert(val1), self.id_or_insert(val2));
+        self.link_ids(ids);
+        let root = self.find(ids.0);
+        self.key(root)
+    }
+
+    /// Links the respective sets of the two provided values if `approve`,
+    /// given the representative and the size of each set, returns true. This
+    /// will insert non-existent values in the process, whether or not the
+    /// link is approved. Returns true if the sets were joined; `approve` is not
+    /// called if they were already the same.
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d"), ("d", "e")]);
+    /// let small = |(_, size1): (&&str, usize), (_, size2): (&&str, usize)| size1 + size2 <= 4;
+    ///
+    /// assert!(!djhs.link_if("a", "c", small));
+    /// assert!(djhs.link_if("a", "f", small));
+    /// assert!(!djhs.is_linked("a", "c"));
+    /// ```
+    pub fn link_if<F>(&mut self, val1: K, val2: K, approve: F) -> bool
+    where
+        F: FnOnce((&K, usize), (&K, usize)) -> bool,
+    {
         let ids = (self.id_or_insert(val1), self.id_or_insert(val2));
         let roots = (self.find(ids.0), self.find(ids.1));
+        let set = |root: PointerId| (self.key(root), self.get(root).size);
+        if roots.0 != roots.1 && !approve(set(roots.0), set(roots.1)) {
+            return false;
+        }
+        self.link_ids(ids)
+    }
 
-        if roots.0 != roots.1 {
-            let ranks = (self.get(roots.0).rank, self.get(roots.1).rank);
+    /// Links every pair of keys in `edges`, inserting non-existent values in
+    /// the process, and reports how many of the edges joined two previously
+    /// disjoint sets and how many were redundant.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{DisjointHashSet, LinkSummary};
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link("a", "b");
+    ///
+    /// let summary = djhs.link_all(vec![("b", "c"), ("c", "a"), ("a", "b"), ("x", "y")]);
+    /// assert_eq!(summary, LinkSummary { merged: 2, redundant: 2 });
+    /// ```
+    pub fn link_all<I: IntoIterator<Item = (K, K)>>(&mut self, edges: I) -> LinkSummary {
+        let mut summary = LinkSummary::default();
 
-            if ranks.0 < ranks.1 {
-                self.get_mut(roots.0).parent = roots.1;
+        for (val1, val2) in edges {
+            let ids = (self.id_or_insert(val1), self.id_or_insert(val2));
+            if self.link_ids(ids) {
+                summary.merged += 1;
             } else {
-                self.get_mut(roots.1).parent = roots.0;
-
-                if ranks.0 == ranks.1 {
-                    self.get_mut(roots.0).rank += 1;
-                };
+                summary.redundant += 1;
             }
         }
+        summary
+    }
+
+    /// The number of disjoint sets.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// assert_eq!(djhs.set_count(), 0);
+    /// djhs.link("a", "b");
+    /// djhs.insert("c");
+    /// assert_eq!(djhs.set_count(), 2);
+    /// ```
+    pub fn set_count(&self) -> usize {
+        self.forest.set_count()
+    }
+
+    /// A number that increases with every insertion and every change to the
+    /// sets, i.e. effective unions, rollbacks and cleared links, for cheap
+    /// invalidation of anything derived from the partition.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link("a", "b");
+    /// let version = djhs.version();
+    ///
+    /// djhs.link("b", "a");
+    /// assert!(djhs.is_linked("a", "b"));
+    /// assert_eq!(djhs.version(), version);
+    ///
+    /// djhs.insert("c");
+    /// assert!(djhs.version() > version);
+    /// ```
+    pub fn version(&self) -> u64 {
+        self.forest.version
+    }
+
+    /// The minimum number of additional links needed to join every key into a
+    /// single set.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link("a", "b");
+    /// djhs.link("c", "d");
+    /// djhs.insert("e");
+    /// assert_eq!(djhs.links_to_connect(), 2);
+    /// ```
+    pub fn links_to_connect(&self) -> usize {
+        self.set_count().saturating_sub(1)
+    }
+
+    /// Records a point that the partition can later be restored to with
+    /// [`rollback_to`](Self::rollback_to).
+    ///
+    /// Taking the first checkpoint enables rollback mode. While active, every
+    /// effective union is kept on a history stack and path compression is
+    /// suspended so that unions can be undone exactly. Queries then cost
+    /// `O(log n)` rather than being amortized near-constant. Rollback mode
+    /// stays active until [`release_checkpoints`](Self::release_checkpoints)
+    /// is called.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link("a", "b");
+    ///
+    /// let checkpoint = djhs.checkpoint();
+    /// djhs.link("b", "c");
+    /// djhs.link("d", "e");
+    /// assert!(djhs.is_linked("a", "c"));
+    ///
+    /// djhs.rollback_to(checkpoint);
+    /// assert!(djhs.is_linked("a", "b"));
+    /// assert!(!djhs.is_linked("a", "c"));
+    /// assert!(!djhs.is_linked("d", "e"));
+    /// ```
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        self.forest.checkpoint()
+    }
+
+    /// Undoes every union performed since the checkpoint was taken. Keys
+    /// inserted since then remain present as singleton sets. Checkpoints taken
+    /// after `checkpoint` are invalidated, while `checkpoint` itself can be
+    /// rolled back to again.
+    ///
+    /// # Panics
+    /// Panics if rollback mode is not active, the checkpoint was taken before
+    /// the history was last released, or it was invalidated by a rollback to
+    /// an earlier checkpoint.
+    ///
+    /// ```should_panic
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// let outer = djhs.checkpoint();
+    /// djhs.link("a", "b");
+    /// let inner = djhs.checkpoint();
+    /// djhs.rollback_to(outer);
+    /// djhs.link("c", "d");
+    /// djhs.rollback_to(inner);
+    /// ```
+    pub fn rollback_to(&mut self, checkpoint: Checkpoint) {
+        self.forest.rollback_to(checkpoint)
+    }
+
+    /// Discards the union history, invalidating all checkpoints and resuming
+    /// path compression.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.checkpoint();
+    /// djhs.link("a", "b");
+    /// djhs.release_checkpoints();
+    /// assert!(djhs.is_linked("a", "b"));
+    /// ```
+    pub fn release_checkpoints(&mut self) {
+        self.forest.release_history();
+    }
+
+    /// Forgets every union, leaving each key a set of its own, while keeping
+    /// the keys and the allocated capacity. This releases all checkpoints and
+    /// discards recorded links, edges and proofs, invalidating sync points.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("b", "c")]);
+    /// djhs.clear_links();
+    /// assert!(djhs.contains("a"));
+    /// assert!(!djhs.is_linked("a", "b"));
+    /// assert_eq!(djhs.set_count(), 3);
+    /// ```
+    pub fn clear_links(&mut self) {
+        self.forest.clear_links()
     }
 
     /// Consumes the DisjointHashSet and returns an iterator of HashSets for
-    /// each disjoint set.
+    /// each disjoint set, in the order their first members were inserted.
+    /// The iterator knows its exact length, so collecting it allocates once.
     ///
     /// ```
     /// use disjoint_hash_set::DisjointHashSet;
     /// use std::{collections::HashSet, iter::FromIterator};
     ///
     /// let edges = vec![("a", "a"), ("b", "c"), ("d", "e"), ("e", "f")];
-    /// let mut sets = DisjointHashSet::from_iter(edges).sets().collect::<Vec<_>>();
+    /// let sets = DisjointHashSet::from_iter(edges).sets();
+    /// assert_eq!(sets.len(), 3);
+    ///
+    /// let mut sets = sets.collect::<Vec<_>>();
     /// sets.sort_by(|set_a, set_b| set_a.len().cmp(&set_b.len()));
     ///
     /// let expected_sets: Vec<HashSet<&str>> = vec![
@@ -197,33 +742,168 @@ impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
     ///
     /// assert_eq!(sets, expected_sets);
     /// ```
-    pub fn sets(mut self) -> impl Iterator<Item = HashSet<K>> {
-        let mut sets = HashMap::new();
+    pub fn sets(self) -> impl ExactSizeIterator<Item = HashSet<K>> + FusedIterator {
+        self.sets_with_hasher(RandomState::new())
+    }
+
+    /// Consumes the DisjointHashSet and returns an iterator of HashSets for
+    /// each disjoint set, each using a clone of the given hash builder. With a
+    /// [`SeededState`], the members of every set are also iterated in the same
+    /// order on every run.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{DisjointHashSet, SeededState};
+    ///
+    /// let edges = vec![("a", "b"), ("c", "d"), ("b", "e")];
+    /// let sets = DisjointHashSet::from_iter(edges).sets_with_hasher(SeededState::new(7));
+    /// let members: Vec<Vec<&str>> = sets.map(|set| set.into_iter().collect()).collect();
+    /// assert_eq!(members.len(), 2);
+    /// assert_eq!(members[0].len(), 3);
+    /// ```
+    pub fn sets_with_hasher<T: BuildHasher + Clone>(
+        self,
+        hash_builder: T,
+    ) -> impl ExactSizeIterator<Item = HashSet<K, T>> + FusedIterator {
+        self.ordered_sets().map(move |members| {
+            let mut set = HashSet::with_capacity_and_hasher(members.len(), hash_builder.clone());
+            set.extend(members);
+            set
+        })
+    }
 
+    /// Consumes the DisjointHashSet and returns every disjoint set as a Vec of
+    /// its members. Both the sets and their members are in insertion order,
+    /// so the output is reproducible regardless of the hasher.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link("b", "d");
+    /// djhs.insert("c");
+    /// djhs.link("a", "b");
+    ///
+    /// let sets: Vec<_> = djhs.ordered_sets().collect();
+    /// assert_eq!(sets, vec![vec!["b", "d", "a"], vec!["c"]]);
+    /// ```
+    pub fn ordered_sets(mut self) -> impl ExactSizeIterator<Item = Vec<K>> + FusedIterator {
         let roots: Vec<PointerId> =
-            (0..self.data.len()).map(|id| self.find(PointerId(id))).collect();
+            (0..self.forest.len()).map(|id| self.find(PointerId(id))).collect();
 
-        self.ids.into_iter().for_each(|(val, id)| {
-            sets.entry(roots[id.0]).or_insert_with(HashSet::new).insert(val);
+        let (mut slots, mut sets) = (vec![None; self.forest.len()], Vec::new());
+        self.keys.into_iter().zip(roots).for_each(|(val, root)| {
+            let slot = *slots[root.0].get_or_insert_with(|| {
+                sets.push(Vec::new());
+                sets.len() - 1
+            });
+            sets[slot].push(val);
         });
 
-        sets.into_iter().map(|(_, set)| set)
+        sets.into_iter()
     }
 
-    fn find(&mut self, id: PointerId) -> PointerId {
-        let parent_id = self.get(id).parent;
-        let grandparent_id = self.get(parent_id).parent;
-        if parent_id == grandparent_id {
-            parent_id
-        } else {
-            let root_id = self.find(parent_id);
-            self.get_mut(id).parent = root_id;
-            root_id
+    /// Returns an iterator over the keys in insertion order.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link("z", "a");
+    /// djhs.insert("m");
+    /// assert_eq!(djhs.keys().collect::<Vec<_>>(), vec![&"z", &"a", &"m"]);
+    /// ```
+    pub fn keys(&self) -> impl ExactSizeIterator<Item = &K> + FusedIterator {
+        self.keys.iter()
+    }
+
+    /// Returns the number of the set of every key, aligned with
+    /// [`keys`](Self::keys). Sets are numbered from `0` in the order of their
+    /// first keys, so the two form a columnar `(key, component)` table.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d"), ("b", "e")]);
+    /// assert_eq!(djhs.keys().collect::<Vec<_>>(), vec![&"a", &"b", &"c", &"d", &"e"]);
+    /// assert_eq!(djhs.component_ids(), vec![0, 0, 1, 1, 0]);
+    /// ```
+    pub fn component_ids(&self) -> Vec<usize> {
+        let mut ids = vec![0; self.forest.len()];
+        for (component, set) in self.grouped_ids().into_iter().enumerate() {
+            set.into_iter().for_each(|id| ids[id.0] = component);
         }
+        ids
+    }
+
+    /// Folds the values of `(key, value)` pairs into one accumulator per
+    /// component, keyed by the representative of that component. Each
+    /// accumulator starts from `init` and components without any values are
+    /// omitted. Pairs whose key is not present are ignored.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link("a", "b");
+    /// djhs.link("c", "d");
+    ///
+    /// let sales = vec![("a", 3), ("b", 4), ("d", 10), ("z", 100)];
+    /// let totals = djhs.aggregate_by_component(sales, || 0, |sum, v| *sum += v);
+    ///
+    /// let mut totals: Vec<_> = totals.into_values().collect();
+    /// totals.sort();
+    /// assert_eq!(totals, vec![7, 10]);
+    /// ```
+    pub fn aggregate_by_component<T, V, A, I, F, G>(
+        &self,
+        pairs: I,
+        mut init: G,
+        mut fold: F,
+    ) -> HashMap<&K, A>
+    where
+        T: Borrow<K>,
+        I: IntoIterator<Item = (T, V)>,
+        F: FnMut(&mut A, V),
+        G: FnMut() -> A,
+    {
+        let mut by_root = HashMap::new();
+
+        for (val, value) in pairs {
+            if let Some(id) = self.id(val.borrow()) {
+                fold(by_root.entry(self.root(id)).or_insert_with(&mut init), value);
+            }
+        }
+
+        by_root.into_iter().map(|(root, acc)| (self.key(root), acc)).collect()
+    }
+
+    fn link_ids(&mut self, ids: (PointerId, PointerId)) -> bool {
+        self.forest.link_ids(ids)
+    }
+
+    fn union(&mut self, id1: PointerId, id2: PointerId) -> Option<(PointerId, PointerId)> {
+        self.forest.union(id1, id2)
+    }
+
+    fn find(&mut self, id: PointerId) -> PointerId {
+        self.forest.find(id)
+    }
+
+    fn grouped_ids(&self) -> Vec<Vec<PointerId>> {
+        self.forest.grouped_ids()
+    }
+
+    fn root(&self, id: PointerId) -> PointerId {
+        self.forest.root(id)
     }
 
     fn id(&self, value: &K) -> Option<PointerId> {
-        self.ids.get(value).copied()
+        let hash = self.hash_builder.hash_one(value);
+        self.index.get(hash, |id| self.key(id) == value)
+    }
+
+    fn key(&self, id: PointerId) -> &K {
+        &self.keys[id.0]
     }
 
     fn id_or_insert(&mut self, value: K) -> PointerId {
@@ -231,18 +911,13 @@ impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
     }
 
     fn insert_unchecked(&mut self, value: K) -> PointerId {
-        let id = PointerId(self.data.len());
-        self.ids.insert(value, id);
-        self.data.push(ParentPointer { parent: id, rank: 0 });
-        id
+        self.index.insert(self.hash_builder.hash_one(&value), PointerId(self.keys.len()));
+        self.keys.push(value);
+        self.forest.push()
     }
 
     fn get(&self, id: PointerId) -> &ParentPointer {
-        &self.data[id.0]
-    }
-
-    fn get_mut(&mut self, id: PointerId) -> &mut ParentPointer {
-        &mut self.data[id.0]
+        self.forest.get(id)
     }
 }
 
@@ -250,11 +925,45 @@ impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
 struct ParentPointer {
     parent: PointerId,
     rank: u8,
+    /// The number of members of the set, maintained for roots only.
+    size: usize,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct PointerId(usize);
 
+/// How many of the edges passed to [`DisjointHashSet::link_all`] joined two
+/// previously disjoint sets, and how many were redundant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinkSummary {
+    /// Edges whose keys were members of different sets.
+    pub merged: usize,
+    /// Edges whose keys were already members of the same set.
+    pub redundant: usize,
+}
+
+/// A point in the union history of a `DisjointHashSet`, obtained from
+/// [`DisjointHashSet::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    unions: usize,
+    edges: usize,
+    epoch: u64,
+    /// The version of the partition when the checkpoint was taken.
+    version: u64,
+}
+
+/// An effective union recorded while rollback mode is active.
+#[derive(Debug, Clone)]
+struct Union {
+    child: PointerId,
+    rank_increased: bool,
+    /// The edge added to the proof forest, if proofs are recorded.
+    proof: Option<(PointerId, PointerId)>,
+    /// The version of the partition before the union.
+    version: u64,
+}
+
 impl<V: Eq + Hash> FromIterator<(V, V)> for DisjointHashSet<V> {
     fn from_iter<I: IntoIterator<Item = (V, V)>>(links: I) -> Self {
         let mut djhs = DisjointHashSet::new();
@@ -0,0 +1,136 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+use crate::{DisjointHashSet, PointerId};
+
+/// Links near-duplicate documents that share a band hash of a MinHash / LSH
+/// pipeline.
+///
+/// Only the first document of every band hash is held in the band index, so
+/// memory grows with the number of distinct band hashes rather than with the
+/// number of pairs fed in. The index can be capped with
+/// [`with_max_buckets`](Self::with_max_buckets), or cleared between bands with
+/// [`clear_buckets`](Self::clear_buckets) when band hashes are fed in band by
+/// band, without losing any links.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::BandIndex;
+///
+/// let mut index = BandIndex::new();
+/// for (document, band_hash) in [("a", 17), ("b", 17), ("c", 42), ("b", 99), ("d", 99)] {
+///     index.insert(document, band_hash);
+/// }
+///
+/// assert!(index.is_linked("a", "d"));
+/// assert!(!index.is_linked("a", "c"));
+/// assert_eq!(index.bucket_count(), 3);
+/// assert_eq!(index.into_inner().set_count(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BandIndex<K, S = RandomState> {
+    djhs: DisjointHashSet<K, S>,
+    buckets: HashMap<u64, PointerId>,
+    max_buckets: Option<usize>,
+    skipped: usize,
+}
+
+impl<K: Eq + Hash> BandIndex<K, RandomState> {
+    /// Creates an empty `BandIndex`.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::BandIndex;
+    /// let mut index: BandIndex<u32> = BandIndex::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    /// Creates an empty `BandIndex` that holds at most `max_buckets` band
+    /// hashes. Once full, band hashes that are not already indexed are
+    /// skipped: their documents are inserted, but not linked through them.
+    ///
+    /// ```
+    /// use disjoint_hash_set::BandIndex;
+    ///
+    /// let mut index = BandIndex::with_max_buckets(1);
+    /// index.insert("a", 1);
+    /// index.insert("b", 2);
+    /// index.insert("c", 2);
+    ///
+    /// assert!(!index.is_linked("b", "c"));
+    /// assert_eq!(index.skipped(), 2);
+    /// ```
+    pub fn with_max_buckets(max_buckets: usize) -> Self {
+        Self { max_buckets: Some(max_buckets), ..Self::new() }
+    }
+}
+
+impl<K: Eq + Hash> Default for BandIndex<K, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> BandIndex<K, S> {
+    /// Creates an empty `BandIndex` which will use the given hasher for the
+    /// documents.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            djhs: DisjointHashSet::with_hasher(hash_builder),
+            buckets: HashMap::new(),
+            max_buckets: None,
+            skipped: 0,
+        }
+    }
+
+    /// Inserts the document, linking it to the documents that share the band
+    /// hash. Returns true if this joined two previously disjoint sets.
+    pub fn insert(&mut self, document: K, band_hash: u64) -> bool {
+        let id = self.djhs.id_or_insert(document);
+        let full = self.max_buckets.is_some_and(|max| self.buckets.len() >= max);
+        let first = match self.buckets.get(&band_hash) {
+            Some(&first) => first,
+            None if full => {
+                self.skipped += 1;
+                return false;
+            }
+            None => *self.buckets.entry(band_hash).or_insert(id),
+        };
+        self.djhs.union(first, id).is_some()
+    }
+
+    /// Checks if the two documents have been linked through shared band
+    /// hashes.
+    pub fn is_linked<T: Borrow<K>>(&mut self, document1: T, document2: T) -> bool {
+        self.djhs.is_linked(document1, document2)
+    }
+
+    /// The number of band hashes held in the index.
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// The number of insertions whose band hash was skipped because the
+    /// index was full. A band hash skipped for several documents counts once
+    /// for each of them.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Clears the band index, releasing its memory while keeping every link.
+    /// Band hashes inserted afterwards no longer link to documents inserted
+    /// before.
+    pub fn clear_buckets(&mut self) {
+        self.buckets = HashMap::new();
+    }
+
+    /// Consumes the index, returning the `DisjointHashSet` of the documents.
+    pub fn into_inner(self) -> DisjointHashSet<K, S> {
+        self.djhs
+    }
+}
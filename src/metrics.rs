@@ -0,0 +1,161 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{DisjointHashSet, PointerId};
+
+/// Similarity measures between two partitions, for evaluating a clustering
+/// against ground truth. Each compares the partitions over the keys present in
+/// either, treating keys present in only one as singletons in the other.
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// The Rand index: the fraction of pairs of keys on which the partitions
+    /// agree, i.e. that are linked in both or in neither. Ranges from `0` to
+    /// `1` for identical partitions.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let truth = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d")]);
+    /// let predicted = DisjointHashSet::from_iter(vec![("a", "b"), ("b", "c"), ("d", "d")]);
+    ///
+    /// // Of the 6 pairs, (a, c), (b, c) and (c, d) are classified differently.
+    /// assert_eq!(predicted.rand_index(&truth), 0.5);
+    /// assert_eq!(truth.rand_index(&truth), 1.0);
+    /// ```
+    pub fn rand_index<T: BuildHasher>(&self, other: &DisjointHashSet<K, T>) -> f64 {
+        let table = Contingency::new(self, other);
+        let total = pairs(table.len);
+        if total == 0.0 {
+            return 1.0;
+        }
+
+        let (both, rows, columns) = table.pair_counts();
+        (total + 2.0 * both - rows - columns) / total
+    }
+
+    /// The Rand index adjusted for chance, which is `1` for identical
+    /// partitions and close to `0` for unrelated ones. It can be negative.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let truth = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d")]);
+    /// let swapped = DisjointHashSet::from_iter(vec![("a", "c"), ("b", "d")]);
+    ///
+    /// assert_eq!(truth.adjusted_rand_index(&truth), 1.0);
+    /// assert!(swapped.adjusted_rand_index(&truth) < 0.0);
+    /// ```
+    pub fn adjusted_rand_index<T: BuildHasher>(&self, other: &DisjointHashSet<K, T>) -> f64 {
+        let table = Contingency::new(self, other);
+        let total = pairs(table.len);
+        if total == 0.0 {
+            return 1.0;
+        }
+
+        let (both, rows, columns) = table.pair_counts();
+        let expected = rows * columns / total;
+        let maximum = (rows + columns) / 2.0;
+        if maximum == expected {
+            return 1.0;
+        }
+        (both - expected) / (maximum - expected)
+    }
+
+    /// The mutual information between the partitions, normalized by the mean
+    /// of their entropies. Ranges from `0` for independent partitions to `1`
+    /// for identical ones.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let truth = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d")]);
+    /// let swapped = DisjointHashSet::from_iter(vec![("a", "c"), ("b", "d")]);
+    ///
+    /// assert!((truth.normalized_mutual_information(&truth) - 1.0).abs() < 1e-12);
+    /// assert!(swapped.normalized_mutual_information(&truth).abs() < 1e-12);
+    /// ```
+    pub fn normalized_mutual_information<T: BuildHasher>(
+        &self,
+        other: &DisjointHashSet<K, T>,
+    ) -> f64 {
+        let table = Contingency::new(self, other);
+        let n = table.len as f64;
+        let entropy = |counts: &HashMap<usize, usize>| {
+            -counts.values().map(|&c| c as f64 / n * (c as f64 / n).ln()).sum::<f64>()
+        };
+
+        let entropies = entropy(&table.rows) + entropy(&table.columns);
+        if entropies == 0.0 {
+            return 1.0;
+        }
+
+        let information: f64 = table
+            .cells
+            .iter()
+            .map(|(&(row, column), &c)| {
+                let (c, row, column) =
+                    (c as f64, table.rows[&row] as f64, table.columns[&column] as f64);
+                c / n * (n * c / (row * column)).ln()
+            })
+            .sum();
+        information / (entropies / 2.0)
+    }
+}
+
+/// The number of keys in each pair of sets of two partitions, with sets
+/// labelled by root id. Keys present in only one partition are labelled past
+/// the ids of the other.
+struct Contingency {
+    cells: HashMap<(usize, usize), usize>,
+    rows: HashMap<usize, usize>,
+    columns: HashMap<usize, usize>,
+    len: usize,
+}
+
+impl Contingency {
+    fn new<K: Eq + Hash, S: BuildHasher, T: BuildHasher>(
+        djhs1: &DisjointHashSet<K, S>,
+        djhs2: &DisjointHashSet<K, T>,
+    ) -> Self {
+        let mut table =
+            Self { cells: HashMap::new(), rows: HashMap::new(), columns: HashMap::new(), len: 0 };
+
+        for (id, key) in djhs1.keys.iter().enumerate() {
+            let row = djhs1.root(PointerId(id)).0;
+            let column = match djhs2.id(key) {
+                Some(id) => djhs2.root(id).0,
+                None => djhs2.keys.len() + table.len,
+            };
+            table.add(row, column);
+        }
+        for (id, key) in djhs2.keys.iter().enumerate() {
+            if djhs1.id(key).is_none() {
+                let row = djhs1.keys.len() + table.len;
+                table.add(row, djhs2.root(PointerId(id)).0);
+            }
+        }
+        table
+    }
+
+    fn add(&mut self, row: usize, column: usize) {
+        *self.cells.entry((row, column)).or_default() += 1;
+        *self.rows.entry(row).or_default() += 1;
+        *self.columns.entry(column).or_default() += 1;
+        self.len += 1;
+    }
+
+    /// The number of pairs of keys sharing a cell, a row, and a column.
+    fn pair_counts(&self) -> (f64, f64, f64) {
+        let sum = |counts: &mut dyn Iterator<Item = &usize>| counts.map(|&c| pairs(c)).sum();
+        (
+            sum(&mut self.cells.values()),
+            sum(&mut self.rows.values()),
+            sum(&mut self.columns.values()),
+        )
+    }
+}
+
+fn pairs(count: usize) -> f64 {
+    count as f64 * count.saturating_sub(1) as f64 / 2.0
+}
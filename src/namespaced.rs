@@ -0,0 +1,213 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+use crate::{KeyIndex, PointerId, forest::Forest};
+
+/// Several independent partitions, named by namespace, over one shared table
+/// of keys, for equivalence relations that each hold over the same keys.
+///
+/// Every key is stored once, however many namespaces it is linked in. A key
+/// is a member of every namespace, as a set of its own until linked there,
+/// and a namespace is created by its first link.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::NamespacedDisjointHashSet;
+///
+/// let mut relations = NamespacedDisjointHashSet::new();
+/// relations.link_in("social", "alice", "bob");
+/// relations.link_in("payments", "bob", "carol");
+///
+/// assert!(relations.is_linked_in("social", "alice", "bob"));
+/// assert!(!relations.is_linked_in("payments", "alice", "bob"));
+/// assert!(relations.is_linked_in("payments", "carol", "bob"));
+/// assert_eq!(relations.len(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NamespacedDisjointHashSet<K, N, S = RandomState> {
+    keys: Vec<K>,
+    index: KeyIndex,
+    hash_builder: S,
+    /// The partition of every namespace, whose ids past its length are
+    /// singletons that have not been reached yet.
+    partitions: HashMap<N, Forest>,
+}
+
+impl<K: Eq + Hash, N: Eq + Hash> NamespacedDisjointHashSet<K, N, RandomState> {
+    /// Creates an empty `NamespacedDisjointHashSet`.
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Eq + Hash, N: Eq + Hash> Default for NamespacedDisjointHashSet<K, N, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, N: Eq + Hash, S: BuildHasher> NamespacedDisjointHashSet<K, N, S> {
+    /// Creates an empty `NamespacedDisjointHashSet` which will use the given
+    /// hash builder to hash keys.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            keys: Vec::new(),
+            index: KeyIndex::default(),
+            hash_builder,
+            partitions: HashMap::new(),
+        }
+    }
+
+    /// The number of keys, across all namespaces.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Checks if there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Check if the value has already been inserted.
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.id(val.borrow()).is_some()
+    }
+
+    /// Insert the value into the key table, a set of its own in every
+    /// namespace. Returns true if the value was not already present.
+    pub fn insert(&mut self, val: K) -> bool {
+        (!self.contains(&val)).then(|| self.insert_unchecked(val)).is_some()
+    }
+
+    /// Returns an iterator over the namespaces that have been linked in, in
+    /// no particular order.
+    pub fn namespaces(&self) -> impl ExactSizeIterator<Item = &N> {
+        self.partitions.keys()
+    }
+
+    /// Removes the namespace, forgetting every link made in it. Returns true
+    /// if it was present. The keys remain.
+    pub fn remove_namespace<Q: ?Sized + Eq + Hash>(&mut self, namespace: &Q) -> bool
+    where
+        N: Borrow<Q>,
+    {
+        self.partitions.remove(namespace).is_some()
+    }
+
+    /// Links the respective sets of the two values in the namespace, creating
+    /// it if needed. This will insert non-existent values in the process.
+    pub fn link_in(&mut self, namespace: N, val1: K, val2: K) {
+        let ids = (self.id_or_insert(val1), self.id_or_insert(val2));
+        let len = self.keys.len();
+        let forest = self.partitions.entry(namespace).or_insert_with(|| Forest::with_capacity(len));
+        while forest.len() < len {
+            forest.push();
+        }
+        forest.union(ids.0, ids.1);
+    }
+
+    /// Checks if the two keys are members of the same set in the namespace.
+    /// This will not implicitly add values or namespaces that were not
+    /// already present.
+    pub fn is_linked_in<Q, T>(&mut self, namespace: &Q, val1: T, val2: T) -> bool
+    where
+        Q: ?Sized + Eq + Hash,
+        N: Borrow<Q>,
+        T: Borrow<K>,
+    {
+        match (self.id(val1.borrow()), self.id(val2.borrow())) {
+            (Some(id1), Some(id2)) => self.find_in(namespace, id1) == self.find_in(namespace, id2),
+            _ => false,
+        }
+    }
+
+    /// Returns the representative of the value's set in the namespace, or
+    /// `None` if the value is not present.
+    ///
+    /// ```
+    /// use disjoint_hash_set::NamespacedDisjointHashSet;
+    ///
+    /// let mut relations = NamespacedDisjointHashSet::new();
+    /// relations.link_in("same-household", 1, 2);
+    /// relations.insert(3);
+    /// assert_eq!(relations.representative_in("same-household", &2), Some(&1));
+    /// assert_eq!(relations.representative_in("same-employer", &2), Some(&2));
+    /// assert_eq!(relations.representative_in("same-employer", &4), None);
+    /// ```
+    pub fn representative_in<Q, T>(&self, namespace: &Q, val: T) -> Option<&K>
+    where
+        Q: ?Sized + Eq + Hash,
+        N: Borrow<Q>,
+        T: Borrow<K>,
+    {
+        let id = self.id(val.borrow())?;
+        let root = match self.partitions.get(namespace) {
+            Some(forest) if id.0 < forest.len() => forest.root(id),
+            _ => id,
+        };
+        Some(&self.keys[root.0])
+    }
+
+    /// The number of disjoint sets of the keys in the namespace.
+    pub fn set_count_in<Q: ?Sized + Eq + Hash>(&self, namespace: &Q) -> usize
+    where
+        N: Borrow<Q>,
+    {
+        let forest = self.partitions.get(namespace);
+        let reached = forest.map_or(0, Forest::len);
+        forest.map_or(0, Forest::set_count) + (self.keys.len() - reached)
+    }
+
+    /// Returns every set of the namespace as a Vec of its members, ordered by
+    /// their first inserted members.
+    ///
+    /// ```
+    /// use disjoint_hash_set::NamespacedDisjointHashSet;
+    ///
+    /// let mut relations = NamespacedDisjointHashSet::new();
+    /// relations.link_in("a", 'x', 'z');
+    /// relations.insert('y');
+    /// assert_eq!(relations.sets_in("a"), vec![vec![&'x', &'z'], vec![&'y']]);
+    /// assert_eq!(relations.sets_in("b"), vec![vec![&'x'], vec![&'z'], vec![&'y']]);
+    /// ```
+    pub fn sets_in<Q: ?Sized + Eq + Hash>(&self, namespace: &Q) -> Vec<Vec<&K>>
+    where
+        N: Borrow<Q>,
+    {
+        let forest = self.partitions.get(namespace);
+        let mut sets = forest.map_or_else(Vec::new, Forest::grouped_ids);
+        let reached = forest.map_or(0, Forest::len);
+        sets.extend((reached..self.keys.len()).map(|id| vec![PointerId(id)]));
+        sets.into_iter().map(|ids| ids.into_iter().map(|id| &self.keys[id.0]).collect()).collect()
+    }
+
+    /// Finds the root of the id in the namespace, compressing its path.
+    fn find_in<Q: ?Sized + Eq + Hash>(&mut self, namespace: &Q, id: PointerId) -> PointerId
+    where
+        N: Borrow<Q>,
+    {
+        match self.partitions.get_mut(namespace) {
+            Some(forest) if id.0 < forest.len() => forest.find(id),
+            _ => id,
+        }
+    }
+
+    fn id(&self, value: &K) -> Option<PointerId> {
+        let hash = self.hash_builder.hash_one(value);
+        self.index.get(hash, |id| self.keys[id.0] == *value)
+    }
+
+    fn id_or_insert(&mut self, value: K) -> PointerId {
+        self.id(&value).unwrap_or_else(|| self.insert_unchecked(value))
+    }
+
+    fn insert_unchecked(&mut self, value: K) -> PointerId {
+        let id = PointerId(self.keys.len());
+        self.index.insert(self.hash_builder.hash_one(&value), id);
+        self.keys.push(value);
+        id
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    borrow::Borrow,
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+use crate::DisjointHashSet;
+
+/// An effective union reported to a [`MergeObserver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Merge<'a, K> {
+    /// The representative of the joined set.
+    pub representative: &'a K,
+    /// The former representative of the set that was absorbed.
+    pub absorbed: &'a K,
+    /// The size of the set previously represented by `representative`.
+    pub representative_size: usize,
+    /// The size of the set previously represented by `absorbed`.
+    pub absorbed_size: usize,
+}
+
+/// Receives every effective union of an [`ObservedDisjointHashSet`].
+///
+/// Implemented for any `FnMut(Merge<K>)` closure.
+pub trait MergeObserver<K> {
+    /// Called after two sets have been joined.
+    fn merged(&mut self, merge: Merge<'_, K>);
+}
+
+impl<K, F: FnMut(Merge<'_, K>)> MergeObserver<K> for F {
+    fn merged(&mut self, merge: Merge<'_, K>) {
+        self(merge)
+    }
+}
+
+/// A `DisjointHashSet` reporting every effective union to a
+/// [`MergeObserver`], so that indexes keyed by representative can be kept in
+/// sync with the partition.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{Merge, ObservedDisjointHashSet};
+///
+/// let mut merges = Vec::new();
+/// let mut djhs = ObservedDisjointHashSet::new(|merge: Merge<&str>| {
+///     merges.push((merge.representative_size, merge.absorbed_size));
+/// });
+///
+/// djhs.link("a", "b");
+/// djhs.link("b", "a");
+/// djhs.link("c", "a");
+/// drop(djhs);
+///
+/// assert_eq!(merges, vec![(1, 1), (2, 1)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ObservedDisjointHashSet<K, O, S = RandomState> {
+    set: DisjointHashSet<K, S>,
+    observer: O,
+}
+
+impl<K: Eq + Hash, O: MergeObserver<K>> ObservedDisjointHashSet<K, O, RandomState> {
+    /// Creates an empty `ObservedDisjointHashSet` reporting to `observer`.
+    pub fn new(observer: O) -> Self {
+        Self { set: DisjointHashSet::new(), observer }
+    }
+}
+
+impl<K: Eq + Hash, O: MergeObserver<K>, S: BuildHasher> ObservedDisjointHashSet<K, O, S> {
+    /// Creates an empty `ObservedDisjointHashSet` reporting to `observer`,
+    /// using `hash_builder` to hash the keys.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::hash::RandomState;
+    /// use disjoint_hash_set::{Merge, ObservedDisjointHashSet};
+    /// let s = RandomState::new();
+    /// let mut djhs = ObservedDisjointHashSet::with_hasher(|_: Merge<&str>| {}, s);
+    /// djhs.link("a", "b");
+    /// ```
+    pub fn with_hasher(observer: O, hash_builder: S) -> Self {
+        Self { set: DisjointHashSet::with_hasher(hash_builder), observer }
+    }
+
+    /// Check if the value has already been inserted.
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.set.contains(val)
+    }
+
+    /// Insert the value as a new disjoint set with a single member. Returns
+    /// true if the value was not already present.
+    pub fn insert(&mut self, val: K) -> bool {
+        self.set.insert(val)
+    }
+
+    /// Checks if the two keys are members of the same set.
+    /// This will not implicitly add values that were not already present.
+    pub fn is_linked<T: Borrow<K>>(&mut self, val1: T, val2: T) -> bool {
+        self.set.is_linked(val1, val2)
+    }
+
+    /// Link the respective sets of the two provided values, notifying the
+    /// observer if they were not already linked. This will insert
+    /// non-existent values in the process.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{Merge, ObservedDisjointHashSet};
+    ///
+    /// let mut representatives = Vec::new();
+    /// let mut djhs = ObservedDisjointHashSet::new(|merge: Merge<u32>| {
+    ///     representatives.push(*merge.representative);
+    /// });
+    /// djhs.link(1, 2);
+    /// djhs.link(3, 1);
+    /// drop(djhs);
+    ///
+    /// assert_eq!(representatives.len(), 2);
+    /// assert_eq!(representatives[0], representatives[1]);
+    /// ```
+    pub fn link(&mut self, val1: K, val2: K) {
+        let ids = (self.set.id_or_insert(val1), self.set.id_or_insert(val2));
+
+        if let Some((root, child)) = self.set.union(ids.0, ids.1) {
+            let absorbed_size = self.set.get(child).size;
+
+            self.observer.merged(Merge {
+                representative: self.set.key(root),
+                absorbed: self.set.key(child),
+                representative_size: self.set.get(root).size - absorbed_size,
+                absorbed_size,
+            });
+        }
+    }
+
+    /// Returns a reference to the observer.
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    /// Returns a mutable reference to the observer.
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
+    }
+
+    /// Consumes the structure, returning the underlying `DisjointHashSet` and
+    /// the observer.
+    pub fn into_parts(self) -> (DisjointHashSet<K, S>, O) {
+        (self.set, self.observer)
+    }
+}
@@ -0,0 +1,148 @@
+use std::{
+    collections::HashMap,
+    hash::{Hash, RandomState},
+};
+
+use crate::{DisjointHashSet, PointerId};
+
+/// An entry of the timeline answered by [`DisjointHashSet::answer_offline`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation<K> {
+    /// Links the two keys.
+    Link(K, K),
+    /// Removes one earlier link between the two keys, in either order. Does
+    /// nothing if there is none.
+    Unlink(K, K),
+    /// Asks whether the two keys are connected at this point of the timeline.
+    IsLinked(K, K),
+}
+
+impl<K: Eq + Hash> DisjointHashSet<K, RandomState> {
+    /// Answers every [`Operation::IsLinked`] query of a timeline of links and
+    /// unlinks, in order.
+    ///
+    /// Since the whole timeline is known in advance, every link is active
+    /// over an interval of queries. The intervals are spread over a segment
+    /// tree of the queries, which is walked with rollback mode active, linking
+    /// on the way down and rolling back on the way up. For `m` operations this
+    /// costs `O(m log m log n)`, without the need for a
+    /// [`DynamicDisjointHashSet`](crate::DynamicDisjointHashSet).
+    ///
+    /// ```
+    /// use disjoint_hash_set::{DisjointHashSet, Operation::*};
+    ///
+    /// let answers = DisjointHashSet::answer_offline([
+    ///     Link("a", "b"),
+    ///     Link("b", "c"),
+    ///     IsLinked("a", "c"),
+    ///     Unlink("c", "b"),
+    ///     IsLinked("a", "c"),
+    ///     IsLinked("a", "b"),
+    /// ]);
+    /// assert_eq!(answers, vec![true, false, true]);
+    /// ```
+    pub fn answer_offline<I: IntoIterator<Item = Operation<K>>>(operations: I) -> Vec<bool> {
+        let mut djhs = Self::new();
+        let mut queries = Vec::new();
+        let mut open: HashMap<(PointerId, PointerId), Vec<usize>> = HashMap::new();
+        let mut intervals = Vec::new();
+
+        for operation in operations {
+            match operation {
+                Operation::Link(val1, val2) => {
+                    let ids = (djhs.id_or_insert(val1), djhs.id_or_insert(val2));
+                    open.entry(edge(ids)).or_default().push(queries.len());
+                }
+                Operation::Unlink(val1, val2) => {
+                    let (Some(id1), Some(id2)) = (djhs.id(&val1), djhs.id(&val2)) else {
+                        continue;
+                    };
+                    if let Some(start) = open.get_mut(&edge((id1, id2))).and_then(Vec::pop) {
+                        intervals.push((start, queries.len(), (id1, id2)));
+                    }
+                }
+                Operation::IsLinked(val1, val2) => {
+                    queries.push((djhs.id_or_insert(val1), djhs.id_or_insert(val2)));
+                }
+            }
+        }
+
+        for (ids, starts) in open {
+            intervals.extend(starts.into_iter().map(|start| (start, queries.len(), ids)));
+        }
+
+        let mut tree = QueryTree { edges: vec![Vec::new(); 4 * queries.len()], len: queries.len() };
+        for (start, end, ids) in intervals.into_iter().filter(|(start, end, _)| start < end) {
+            tree.add(1, 0, tree.len, (start, end), ids);
+        }
+
+        let mut answers = vec![false; queries.len()];
+        if !queries.is_empty() {
+            tree.answer(&mut djhs, 1, 0, queries.len(), &queries, &mut answers);
+        }
+        answers
+    }
+}
+
+/// The ids of an undirected edge, in a canonical order.
+fn edge((id1, id2): (PointerId, PointerId)) -> (PointerId, PointerId) {
+    (id1.min(id2), id1.max(id2))
+}
+
+/// A segment tree over the queries of a timeline, holding at every node the
+/// links active throughout its range of queries but not its parent's.
+struct QueryTree {
+    edges: Vec<Vec<(PointerId, PointerId)>>,
+    len: usize,
+}
+
+impl QueryTree {
+    /// Adds an edge active over the queries in `range` below `node`, which
+    /// covers the queries `lo..hi`.
+    fn add(
+        &mut self,
+        node: usize,
+        lo: usize,
+        hi: usize,
+        range: (usize, usize),
+        ids: (PointerId, PointerId),
+    ) {
+        if range.1 <= lo || hi <= range.0 {
+            return;
+        }
+        if range.0 <= lo && hi <= range.1 {
+            self.edges[node].push(ids);
+            return;
+        }
+
+        let mid = (lo + hi) / 2;
+        self.add(2 * node, lo, mid, range, ids);
+        self.add(2 * node + 1, mid, hi, range, ids);
+    }
+
+    fn answer<K: Eq + Hash>(
+        &self,
+        djhs: &mut DisjointHashSet<K>,
+        node: usize,
+        lo: usize,
+        hi: usize,
+        queries: &[(PointerId, PointerId)],
+        answers: &mut [bool],
+    ) {
+        let checkpoint = djhs.checkpoint();
+        for &(id1, id2) in &self.edges[node] {
+            djhs.union(id1, id2);
+        }
+
+        if hi - lo == 1 {
+            let (id1, id2) = queries[lo];
+            answers[lo] = djhs.root(id1) == djhs.root(id2);
+        } else {
+            let mid = (lo + hi) / 2;
+            self.answer(djhs, 2 * node, lo, mid, queries, answers);
+            self.answer(djhs, 2 * node + 1, mid, hi, queries, answers);
+        }
+
+        djhs.rollback_to(checkpoint);
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, BTreeSet},
+    iter::FusedIterator,
+    ops::RangeBounds,
+};
+
+use crate::{ParentPointer, PointerId};
+
+/// A disjoint set over keys that are only `Ord`, not `Hash`.
+///
+/// Keys are held in a `BTreeMap`, so lookups cost `O(log n)`, keys and sets
+/// come out in sorted order, and whole ranges of keys can be linked at once.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::DisjointBTreeSet;
+/// use std::collections::BTreeSet;
+///
+/// let mut djbs = DisjointBTreeSet::new();
+/// djbs.link(3, 1);
+/// djbs.link(5, 4);
+/// djbs.insert(2);
+/// djbs.link_range(4..=6);
+///
+/// assert!(djbs.is_linked(1, 3));
+/// let sets: Vec<BTreeSet<i32>> = djbs.sets().collect();
+/// assert_eq!(sets, vec![BTreeSet::from([1, 3]), BTreeSet::from([2]), BTreeSet::from([4, 5])]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DisjointBTreeSet<K> {
+    ids: BTreeMap<K, PointerId>,
+    data: Vec<ParentPointer>,
+}
+
+impl<K: Ord> DisjointBTreeSet<K> {
+    /// Creates an empty `DisjointBTreeSet`.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::DisjointBTreeSet;
+    /// let mut djbs: DisjointBTreeSet<&str> = DisjointBTreeSet::new();
+    /// ```
+    pub fn new() -> Self {
+        Self { ids: BTreeMap::new(), data: Vec::new() }
+    }
+
+    /// Check if the value has already been inserted.
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.ids.contains_key(val.borrow())
+    }
+
+    /// Insert the value as a new disjoint set with a single member. Returns
+    /// true if the value was not already present.
+    pub fn insert(&mut self, val: K) -> bool {
+        (!self.contains(&val)).then(|| self.insert_unchecked(val)).is_some()
+    }
+
+    /// Checks if the two keys are members of the same set.
+    /// This will not implicitly add values that were not already present.
+    pub fn is_linked<T: Borrow<K>>(&mut self, val1: T, val2: T) -> bool {
+        match (self.ids.get(val1.borrow()), self.ids.get(val2.borrow())) {
+            (Some(&id1), Some(&id2)) => self.find(id1) == self.find(id2),
+            _ => false,
+        }
+    }
+
+    /// Link the respective sets of the two provided values. This will insert
+    /// non-existent values in the process.
+    pub fn link(&mut self, val1: K, val2: K) {
+        let ids = (self.id_or_insert(val1), self.id_or_insert(val2));
+        self.union(ids.0, ids.1);
+    }
+
+    /// Links the sets of every key already present within the range. Keys in
+    /// the range that are not present are not inserted.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointBTreeSet;
+    ///
+    /// let mut djbs = DisjointBTreeSet::new();
+    /// for fruit in ["apple", "banana", "cherry", "date"] {
+    ///     djbs.insert(fruit);
+    /// }
+    /// djbs.link_range("b".."d");
+    ///
+    /// assert!(djbs.is_linked("banana", "cherry"));
+    /// assert!(!djbs.is_linked("cherry", "date"));
+    /// ```
+    pub fn link_range<T: Ord + ?Sized, R: RangeBounds<T>>(&mut self, range: R)
+    where
+        K: Borrow<T>,
+    {
+        let ids: Vec<PointerId> = self.ids.range(range).map(|(_, &id)| id).collect();
+        ids.windows(2).for_each(|pair| self.union(pair[0], pair[1]));
+    }
+
+    /// Returns an iterator over the present keys within the range, in order.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointBTreeSet;
+    ///
+    /// let mut djbs = DisjointBTreeSet::new();
+    /// djbs.link(10, 40);
+    /// djbs.link(20, 30);
+    /// assert_eq!(djbs.range(15..=40).collect::<Vec<_>>(), vec![&20, &30, &40]);
+    /// ```
+    pub fn range<T: Ord + ?Sized, R: RangeBounds<T>>(&self, range: R) -> impl Iterator<Item = &K>
+    where
+        K: Borrow<T>,
+    {
+        self.ids.range(range).map(|(key, _)| key)
+    }
+
+    /// Consumes the DisjointBTreeSet and returns an iterator of BTreeSets for
+    /// each disjoint set, ordered by their smallest members.
+    pub fn sets(mut self) -> impl ExactSizeIterator<Item = BTreeSet<K>> + FusedIterator {
+        let roots: Vec<PointerId> =
+            (0..self.data.len()).map(|id| self.find(PointerId(id))).collect();
+
+        let (mut slots, mut sets) = (vec![None; self.data.len()], Vec::new());
+        self.ids.into_iter().for_each(|(val, id)| {
+            let slot = *slots[roots[id.0].0].get_or_insert_with(|| {
+                sets.push(BTreeSet::new());
+                sets.len() - 1
+            });
+            sets[slot].insert(val);
+        });
+
+        sets.into_iter()
+    }
+
+    fn union(&mut self, id1: PointerId, id2: PointerId) {
+        let roots = (self.find(id1), self.find(id2));
+        if roots.0 == roots.1 {
+            return;
+        }
+
+        let ranks = (self.data[roots.0.0].rank, self.data[roots.1.0].rank);
+        let (root, child) = if ranks.0 < ranks.1 { (roots.1, roots.0) } else { roots };
+        self.data[child.0].parent = root;
+        self.data[root.0].size += self.data[child.0].size;
+        if ranks.0 == ranks.1 {
+            self.data[root.0].rank += 1;
+        }
+    }
+
+    fn find(&mut self, id: PointerId) -> PointerId {
+        let parent = self.data[id.0].parent;
+        if parent == id {
+            return id;
+        }
+
+        let root = self.find(parent);
+        self.data[id.0].parent = root;
+        root
+    }
+
+    fn id_or_insert(&mut self, value: K) -> PointerId {
+        self.ids.get(&value).copied().unwrap_or_else(|| self.insert_unchecked(value))
+    }
+
+    fn insert_unchecked(&mut self, value: K) -> PointerId {
+        let id = PointerId(self.data.len());
+        self.ids.insert(value, id);
+        self.data.push(ParentPointer { parent: id, rank: 0, size: 1 });
+        id
+    }
+}
+
+impl<K: Ord> Default for DisjointBTreeSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,153 @@
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, Hash, RandomState},
+    iter::FusedIterator,
+};
+
+use crate::{Contradiction, WeightedDisjointHashSet};
+
+/// A `DisjointHashSet` whose links state whether two keys are equal or
+/// unequal, e.g. friends or enemies, or the same or opposite sides of a
+/// bipartition.
+///
+/// This is a [`WeightedDisjointHashSet`] over the parity group, so the relation
+/// between any two linked keys can be derived, and links contradicting a known
+/// relation fail with a [`Contradiction`].
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::ParityDisjointHashSet;
+///
+/// let mut djhs = ParityDisjointHashSet::new();
+/// djhs.link_unequal("a", "b").unwrap();
+/// djhs.link_unequal("b", "c").unwrap();
+///
+/// assert_eq!(djhs.is_equal("a", "c"), Some(true));
+/// assert_eq!(djhs.is_equal("a", "d"), None);
+/// assert!(djhs.link_unequal("c", "a").is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParityDisjointHashSet<K, S = RandomState> {
+    inner: WeightedDisjointHashSet<K, bool, S>,
+}
+
+impl<K: Eq + Hash> ParityDisjointHashSet<K, RandomState> {
+    /// Creates an empty `ParityDisjointHashSet`.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::ParityDisjointHashSet;
+    /// let mut djhs: ParityDisjointHashSet<&str> = ParityDisjointHashSet::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Eq + Hash> Default for ParityDisjointHashSet<K, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> ParityDisjointHashSet<K, S> {
+    /// Creates an empty `ParityDisjointHashSet` which will use the given hash
+    /// builder to hash keys.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::hash::RandomState;
+    /// use disjoint_hash_set::ParityDisjointHashSet;
+    /// let s = RandomState::new();
+    /// let mut djhs: ParityDisjointHashSet<&str> = ParityDisjointHashSet::with_hasher(s);
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self { inner: WeightedDisjointHashSet::with_hasher(hash_builder) }
+    }
+
+    /// Check if the value has already been inserted.
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.inner.contains(val)
+    }
+
+    /// Insert the value as a new disjoint set with a single member. Returns
+    /// true if the value was not already present.
+    pub fn insert(&mut self, val: K) -> bool {
+        self.inner.insert(val)
+    }
+
+    /// Checks if the two keys are members of the same set, i.e. if their
+    /// relation is known. This will not implicitly add values that were not
+    /// already present.
+    pub fn is_linked<T: Borrow<K>>(&mut self, val1: T, val2: T) -> bool {
+        self.is_equal(val1, val2).is_some()
+    }
+
+    /// Returns whether the two keys are equal, or `None` if their relation is
+    /// unknown because they are not linked.
+    ///
+    /// ```
+    /// use disjoint_hash_set::ParityDisjointHashSet;
+    ///
+    /// let mut djhs = ParityDisjointHashSet::new();
+    /// djhs.link_equal(1, 2).unwrap();
+    /// djhs.link_unequal(2, 3).unwrap();
+    /// assert_eq!(djhs.is_equal(1, 2), Some(true));
+    /// assert_eq!(djhs.is_equal(3, 1), Some(false));
+    /// ```
+    pub fn is_equal<T: Borrow<K>>(&mut self, val1: T, val2: T) -> Option<bool> {
+        self.inner.relation(val1, val2).map(|parity| !parity)
+    }
+
+    /// Links the two values as equal. This will insert non-existent values in
+    /// the process. Fails, returning the values, if they are known to be
+    /// unequal.
+    pub fn link_equal(&mut self, val1: K, val2: K) -> Result<(), Contradiction<K>> {
+        self.inner.link(val1, val2, false)
+    }
+
+    /// Links the two values as unequal. This will insert non-existent values
+    /// in the process. Fails, returning the values, if they are known to be
+    /// equal.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{Contradiction, ParityDisjointHashSet};
+    ///
+    /// // An odd cycle can't be two-colored.
+    /// let mut djhs = ParityDisjointHashSet::new();
+    /// djhs.link_unequal("a", "b").unwrap();
+    /// djhs.link_unequal("b", "c").unwrap();
+    /// assert_eq!(djhs.link_unequal("c", "a"), Err(Contradiction("c", "a")));
+    /// ```
+    pub fn link_unequal(&mut self, val1: K, val2: K) -> Result<(), Contradiction<K>> {
+        self.inner.link(val1, val2, true)
+    }
+
+    /// Consumes the structure and returns a two-coloring of each disjoint
+    /// set, i.e. its members split into two sets such that equal keys share a
+    /// side and unequal keys don't. Which side is listed first is arbitrary.
+    ///
+    /// ```
+    /// use disjoint_hash_set::ParityDisjointHashSet;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut djhs = ParityDisjointHashSet::new();
+    /// djhs.link_unequal("a", "b").unwrap();
+    /// djhs.link_equal("b", "c").unwrap();
+    ///
+    /// let (side1, side2) = djhs.colorings().next().unwrap();
+    /// let mut sides = [side1, side2];
+    /// sides.sort_by_key(HashSet::len);
+    /// assert_eq!(sides, [HashSet::from(["a"]), HashSet::from(["b", "c"])]);
+    /// ```
+    pub fn colorings(
+        self,
+    ) -> impl ExactSizeIterator<Item = (HashSet<K>, HashSet<K>)> + FusedIterator {
+        self.inner.sets().map(|set| {
+            let (side1, side2): (HashMap<K, bool>, _) =
+                set.into_iter().partition(|(_, parity)| !parity);
+            (side1.into_keys().collect(), side2.into_keys().collect())
+        })
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{DisjointHashSet, PointerId};
+
+impl<K: Eq + Hash + Clone, S: BuildHasher + Clone> DisjointHashSet<K, S> {
+    /// Builds the quotient partition: a new `DisjointHashSet` over the
+    /// [`representative`](Self::representative) of every set, with the
+    /// representatives linked by `edges`.
+    ///
+    /// Each key of an edge is replaced by its representative, so any member
+    /// may stand in for its set. Keys that are not present are their own
+    /// representatives. Repeatedly coarsening the result gives a hierarchy of
+    /// ever coarser clusterings.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let fine = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d"), ("e", "f")]);
+    /// let mut coarse = fine.coarsen(vec![("b", "c")]);
+    ///
+    /// assert_eq!(coarse.set_count(), 2);
+    /// let (a, d, e) = (fine.representative("a"), fine.representative("d"), fine.representative("e"));
+    /// assert!(coarse.is_linked(a.unwrap(), d.unwrap()));
+    /// assert!(!coarse.is_linked(a.unwrap(), e.unwrap()));
+    /// ```
+    pub fn coarsen<I: IntoIterator<Item = (K, K)>>(&self, edges: I) -> DisjointHashSet<K, S> {
+        let mut coarse = DisjointHashSet::with_hasher(self.hash_builder.clone());
+        for (id, pointer) in self.forest.data.iter().enumerate() {
+            if pointer.parent.0 == id {
+                coarse.insert_unchecked(self.keys[id].clone());
+            }
+        }
+
+        let representative = |val: K| self.representative(&val).cloned().unwrap_or(val);
+        for (val1, val2) in edges {
+            coarse.link(representative(val1), representative(val2));
+        }
+        coarse
+    }
+
+    /// The join of the two partitions: the finest partition that both refine,
+    /// in which keys are linked if a chain of sets from either partition
+    /// connects them. Keys present in only one partition are included.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let by_email = DisjointHashSet::from_iter(vec![("ann", "bea"), ("cid", "dan")]);
+    /// let by_phone = DisjointHashSet::from_iter(vec![("bea", "cid"), ("eve", "eve")]);
+    ///
+    /// let mut joined = by_email.join(&by_phone);
+    /// assert!(joined.is_linked("ann", "dan"));
+    /// assert_eq!(joined.set_count(), 2);
+    /// ```
+    pub fn join<T: BuildHasher>(&self, other: &DisjointHashSet<K, T>) -> DisjointHashSet<K, S> {
+        let mut joined = DisjointHashSet::with_capacity_and_hasher(
+            self.keys.len().max(other.keys.len()),
+            self.hash_builder.clone(),
+        );
+        joined.link_representatives(self);
+        joined.link_representatives(other);
+        joined
+    }
+
+    /// The meet of the two partitions: their coarsest common refinement, in
+    /// which keys are linked if they are members of the same set in both.
+    /// Keys present in only one partition are included as singletons.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let by_city = DisjointHashSet::from_iter(vec![("ann", "bea"), ("bea", "cid")]);
+    /// let by_team = DisjointHashSet::from_iter(vec![("ann", "bea"), ("cid", "dan")]);
+    ///
+    /// let mut met = by_city.meet(&by_team);
+    /// assert!(met.is_linked("ann", "bea"));
+    /// assert!(!met.is_linked("bea", "cid"));
+    /// assert_eq!(met.set_count(), 3);
+    /// ```
+    pub fn meet<T: BuildHasher>(&self, other: &DisjointHashSet<K, T>) -> DisjointHashSet<K, S> {
+        let mut met = DisjointHashSet::with_capacity_and_hasher(
+            self.keys.len().max(other.keys.len()),
+            self.hash_builder.clone(),
+        );
+
+        let mut firsts = HashMap::new();
+        for (id, key) in self.keys.iter().enumerate() {
+            match other.id(key) {
+                Some(other_id) => {
+                    let roots = (self.root(PointerId(id)), other.root(other_id));
+                    let first = *firsts.entry(roots).or_insert(key);
+                    met.link(key.clone(), first.clone());
+                }
+                None => {
+                    met.insert(key.clone());
+                }
+            }
+        }
+        for key in &other.keys {
+            met.insert(key.clone());
+        }
+        met
+    }
+
+    /// Inserts every key of `other`, linked to its representative there.
+    fn link_representatives<T: BuildHasher>(&mut self, other: &DisjointHashSet<K, T>) {
+        for (id, key) in other.keys.iter().enumerate() {
+            let representative = other.key(other.root(PointerId(id)));
+            self.link(key.clone(), representative.clone());
+        }
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Checks if every set of this partition is contained in a set of
+    /// `other`, i.e. if `other` can be reached from this one by linking alone.
+    /// Keys present in only one partition are treated as singletons in the
+    /// other.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let batch = DisjointHashSet::from_iter(vec![("a", "b"), ("b", "c"), ("d", "e")]);
+    /// let mut incremental = DisjointHashSet::from_iter(vec![("a", "b"), ("d", "d")]);
+    /// assert!(incremental.is_refinement_of(&batch));
+    /// assert!(!batch.is_refinement_of(&incremental));
+    ///
+    /// incremental.link("c", "d");
+    /// assert!(!incremental.is_refinement_of(&batch));
+    /// ```
+    pub fn is_refinement_of<T: BuildHasher>(&self, other: &DisjointHashSet<K, T>) -> bool {
+        let mut containing = HashMap::new();
+        self.keys.iter().enumerate().all(|(id, key)| {
+            let root = self.root(PointerId(id));
+            match other.id(key) {
+                Some(other_id) => {
+                    let other_root = other.root(other_id);
+                    *containing.entry(root).or_insert(other_root) == other_root
+                }
+                None => self.get(root).size == 1,
+            }
+        })
+    }
+
+    /// Compares this partition with a `newer` one over mostly the same keys,
+    /// listing the sets that were merged or split along with the keys that
+    /// moved, appeared, or disappeared. Sets are named by their
+    /// [`representative`](Self::representative), lists are in insertion order
+    /// of the keys and representatives they hold.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let old = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d"), ("e", "f")]);
+    /// let new = DisjointHashSet::from_iter(vec![("a", "b"), ("b", "c"), ("d", "x")]);
+    ///
+    /// let diff = old.diff(&new);
+    /// let c_in_new = new.representative("c").unwrap();
+    /// let (a_in_old, c_in_old) = (old.representative("a").unwrap(), old.representative("c").unwrap());
+    ///
+    /// assert_eq!(diff.merged, vec![(c_in_new, vec![a_in_old, c_in_old])]);
+    /// assert_eq!(diff.split, vec![(c_in_old, vec![c_in_new, new.representative("d").unwrap()])]);
+    /// assert_eq!(diff.moved, vec![&"a", &"b", &"c", &"d"]);
+    /// assert_eq!(diff.added, vec![&"x"]);
+    /// assert_eq!(diff.removed, vec![&"e", &"f"]);
+    /// ```
+    pub fn diff<'a, T: BuildHasher>(
+        &'a self,
+        newer: &'a DisjointHashSet<K, T>,
+    ) -> PartitionDiff<'a, K> {
+        let mut shared = Vec::new();
+        let mut removed = Vec::new();
+        let mut cells: HashMap<(PointerId, PointerId), usize> = HashMap::new();
+        let mut successors: BTreeMap<PointerId, BTreeSet<PointerId>> = BTreeMap::new();
+        let mut predecessors: BTreeMap<PointerId, BTreeSet<PointerId>> = BTreeMap::new();
+        let (mut old_sizes, mut new_sizes) = (HashMap::new(), HashMap::new());
+
+        for (id, key) in self.keys.iter().enumerate() {
+            let Some(newer_id) = newer.id(key) else {
+                removed.push(key);
+                continue;
+            };
+
+            let roots = (self.root(PointerId(id)), newer.root(newer_id));
+            *cells.entry(roots).or_default() += 1;
+            *old_sizes.entry(roots.0).or_insert(0) += 1;
+            *new_sizes.entry(roots.1).or_insert(0) += 1;
+            successors.entry(roots.0).or_default().insert(roots.1);
+            predecessors.entry(roots.1).or_default().insert(roots.0);
+            shared.push((key, roots));
+        }
+
+        let merged = predecessors
+            .into_iter()
+            .filter(|(_, olds)| olds.len() > 1)
+            .map(|(root, olds)| {
+                (newer.key(root), olds.into_iter().map(|old| self.key(old)).collect())
+            })
+            .collect();
+        let split = successors
+            .into_iter()
+            .filter(|(_, news)| news.len() > 1)
+            .map(|(root, news)| {
+                (self.key(root), news.into_iter().map(|new| newer.key(new)).collect())
+            })
+            .collect();
+
+        let moved = shared
+            .into_iter()
+            .filter(|(_, roots)| {
+                let cell = cells[roots];
+                cell != old_sizes[&roots.0] || cell != new_sizes[&roots.1]
+            })
+            .map(|(key, _)| key)
+            .collect();
+        let added = newer.keys.iter().filter(|key| self.id(key).is_none()).collect();
+
+        PartitionDiff { merged, split, moved, added, removed }
+    }
+
+    /// Builds the condensed graph of the edges: the edges between different
+    /// sets, with both ends replaced by the representatives of their sets, and
+    /// counted by multiplicity. Edges are kept in their direction, and edges
+    /// with a key that is not present are ignored.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d")]);
+    /// let edges = [("a", "c"), ("b", "d"), ("a", "b"), ("d", "a"), ("a", "z")];
+    /// let condensed = djhs.condense(edges);
+    ///
+    /// let (ab, cd) = (djhs.representative("a").unwrap(), djhs.representative("c").unwrap());
+    /// assert_eq!(condensed.len(), 2);
+    /// assert_eq!(condensed[&(ab, cd)], 2);
+    /// assert_eq!(condensed[&(cd, ab)], 1);
+    /// ```
+    pub fn condense<T, I>(&self, edges: I) -> HashMap<(&K, &K), usize>
+    where
+        T: Borrow<K>,
+        I: IntoIterator<Item = (T, T)>,
+    {
+        let mut condensed = HashMap::new();
+        for (val1, val2) in edges {
+            let (Some(id1), Some(id2)) = (self.id(val1.borrow()), self.id(val2.borrow())) else {
+                continue;
+            };
+
+            let roots = (self.root(id1), self.root(id2));
+            if roots.0 != roots.1 {
+                *condensed.entry((self.key(roots.0), self.key(roots.1))).or_default() += 1;
+            }
+        }
+        condensed
+    }
+
+    /// Counts the edges inside a set and the edges between different sets,
+    /// e.g. to score a clustering against the graph it was built from. Edges
+    /// with a key that is not present are ignored.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{DisjointHashSet, EdgeCounts};
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d")]);
+    /// let edges = [("a", "b"), ("b", "a"), ("a", "c"), ("d", "c"), ("a", "z")];
+    /// assert_eq!(djhs.count_edges(edges), EdgeCounts { internal: 3, external: 1 });
+    /// ```
+    pub fn count_edges<T, I>(&self, edges: I) -> EdgeCounts
+    where
+        T: Borrow<K>,
+        I: IntoIterator<Item = (T, T)>,
+    {
+        let mut counts = EdgeCounts::default();
+        for (val1, val2) in edges {
+            let (Some(id1), Some(id2)) = (self.id(val1.borrow()), self.id(val2.borrow())) else {
+                continue;
+            };
+
+            if self.root(id1) == self.root(id2) {
+                counts.internal += 1;
+            } else {
+                counts.external += 1;
+            }
+        }
+        counts
+    }
+}
+
+/// The changes from one partition to a newer one, as returned by
+/// [`DisjointHashSet::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionDiff<'a, K> {
+    /// Every newer set holding keys of more than one older set, along with
+    /// those older sets.
+    pub merged: Vec<(&'a K, Vec<&'a K>)>,
+    /// Every older set whose keys are spread over more than one newer set,
+    /// along with those newer sets.
+    pub split: Vec<(&'a K, Vec<&'a K>)>,
+    /// Keys present in both whose set gained or lost members present in both.
+    pub moved: Vec<&'a K>,
+    /// Keys present only in the newer partition.
+    pub added: Vec<&'a K>,
+    /// Keys present only in the older partition.
+    pub removed: Vec<&'a K>,
+}
+
+/// How many edges passed to [`DisjointHashSet::count_edges`] lie inside a set,
+/// and how many between different sets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EdgeCounts {
+    /// Edges whose keys are members of the same set.
+    pub internal: usize,
+    /// Edges whose keys are members of different sets.
+    pub external: usize,
+}
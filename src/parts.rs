@@ -0,0 +1,221 @@
+use std::{
+    error::Error,
+    fmt,
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+use crate::{DisjointHashSet, KeyIndex, ParentPointer, PointerId, forest::Forest};
+
+/// The raw parts of a `DisjointHashSet`, as returned by
+/// [`DisjointHashSet::into_parts`], from which the structure is restored
+/// as is, without relinking its keys.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Parts<K> {
+    /// The keys, in insertion order.
+    pub keys: Vec<K>,
+    /// The index of the parent of every key, or its own index for roots.
+    pub parents: Vec<usize>,
+    /// The rank of every key, bounding the height of its tree.
+    pub ranks: Vec<u8>,
+}
+
+/// A violated invariant of a `DisjointHashSet`, as found by
+/// [`DisjointHashSet::validate`] or when restoring raw parts with
+/// [`DisjointHashSet::from_parts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntegrityError {
+    /// There is not a parent and a rank for every key.
+    LengthMismatch,
+    /// The key at this index is also present at an earlier index.
+    DuplicateKey(usize),
+    /// The key at this index can't be found by its hash.
+    MissingKey(usize),
+    /// The parent of the key at this index is out of range.
+    ParentOutOfRange(usize),
+    /// The key at this index has a rank that no sequence of unions produces:
+    /// no greater than that of its child, or too great for the size of its
+    /// set.
+    InvalidRank(usize),
+    /// The set represented by the key at this index has a recorded size that
+    /// differs from its number of members.
+    SizeMismatch(usize),
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::LengthMismatch => f.write_str("keys and parents differ in length"),
+            IntegrityError::DuplicateKey(id) => write!(f, "key {id} is a duplicate"),
+            IntegrityError::MissingKey(id) => write!(f, "key {id} is missing from the index"),
+            IntegrityError::ParentOutOfRange(id) => write!(f, "parent of key {id} is out of range"),
+            IntegrityError::InvalidRank(id) => write!(f, "rank of key {id} is invalid"),
+            IntegrityError::SizeMismatch(id) => write!(f, "size of the set of key {id} is wrong"),
+        }
+    }
+}
+
+impl Error for IntegrityError {}
+
+impl<K: Eq + Hash> DisjointHashSet<K, RandomState> {
+    /// Restores a `DisjointHashSet` from its raw parts, which may come from an
+    /// untrusted source. The parts are validated first, so that a structure
+    /// that would panic or never finish a later operation is rejected.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::{DisjointHashSet, IntegrityError, Parts};
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d"), ("b", "e")]);
+    /// let parts = djhs.into_parts();
+    /// assert_eq!(parts.keys, vec!["a", "b", "c", "d", "e"]);
+    ///
+    /// let mut djhs = DisjointHashSet::from_parts(parts).unwrap();
+    /// assert!(djhs.is_linked("a", "e"));
+    /// assert_eq!(djhs.set_count(), 2);
+    ///
+    /// // A parent cycle is rejected.
+    /// let cycle = Parts { keys: vec!["a", "b"], parents: vec![1, 0], ranks: vec![1, 1] };
+    /// assert_eq!(DisjointHashSet::from_parts(cycle).unwrap_err(), IntegrityError::InvalidRank(1));
+    /// ```
+    pub fn from_parts(parts: Parts<K>) -> Result<Self, IntegrityError> {
+        Self::from_parts_with_hasher(parts, RandomState::new())
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Restores a `DisjointHashSet` from its raw parts, using `hasher` to hash
+    /// the keys. The parts are validated as by
+    /// [`from_parts`](DisjointHashSet::from_parts).
+    pub fn from_parts_with_hasher(parts: Parts<K>, hasher: S) -> Result<Self, IntegrityError> {
+        let Parts { keys, parents, ranks } = parts;
+        if keys.len() != parents.len() || keys.len() != ranks.len() {
+            return Err(IntegrityError::LengthMismatch);
+        }
+
+        let mut index = KeyIndex::with_capacity(keys.len());
+        keys.iter()
+            .enumerate()
+            .for_each(|(id, key)| index.insert(hasher.hash_one(key), PointerId(id)));
+        let data = parents
+            .into_iter()
+            .zip(ranks)
+            .map(|(parent, rank)| ParentPointer { parent: PointerId(parent), rank, size: 0 })
+            .collect();
+        let mut djhs = Self { keys, index, hash_builder: hasher, forest: Forest::from_data(data) };
+
+        djhs.validate_forest()?;
+        for id in 0..djhs.forest.len() {
+            let root = djhs.root(PointerId(id));
+            djhs.forest.get_mut(root).size += 1;
+        }
+        djhs.validate()?;
+        Ok(djhs)
+    }
+
+    /// Verifies the invariants of the structure: every key can be found by
+    /// its hash, parents are in range and form trees without cycles, ranks
+    /// are bounded as by union by rank, and every set records its size.
+    ///
+    /// A structure built through this crate's API always passes; this is
+    /// meant for debug assertions on code that embeds it.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d"), ("b", "d")]);
+    /// djhs.insert("e");
+    /// assert_eq!(djhs.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), IntegrityError> {
+        if self.keys.len() != self.forest.len() {
+            return Err(IntegrityError::LengthMismatch);
+        }
+        for (id, key) in self.keys.iter().enumerate() {
+            match self.id(key) {
+                None => return Err(IntegrityError::MissingKey(id)),
+                Some(found) if found.0 != id => return Err(IntegrityError::DuplicateKey(id)),
+                Some(_) => {}
+            }
+        }
+        self.validate_forest()?;
+
+        let mut sizes = vec![0; self.forest.len()];
+        for id in (0..self.forest.len()).map(PointerId) {
+            sizes[self.root(id).0] += 1;
+        }
+        for (id, size) in sizes.into_iter().enumerate() {
+            let pointer = &self.forest.data[id];
+            if pointer.parent.0 != id {
+                continue;
+            }
+            if pointer.size != size {
+                return Err(IntegrityError::SizeMismatch(id));
+            }
+            // Union by rank only raises the rank of a root to `r` once its set
+            // holds at least `2^r` keys.
+            if size.checked_shr(pointer.rank.into()).is_none_or(|bound| bound == 0) {
+                return Err(IntegrityError::InvalidRank(id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifies that parents are in range and that ranks strictly increase
+    /// towards the roots, which rules out cycles and bounds the height of every
+    /// tree.
+    fn validate_forest(&self) -> Result<(), IntegrityError> {
+        for (id, pointer) in self.forest.data.iter().enumerate() {
+            let parent = pointer.parent.0;
+            match self.forest.data.get(parent) {
+                None => return Err(IntegrityError::ParentOutOfRange(id)),
+                Some(up) if parent != id && up.rank <= pointer.rank => {
+                    return Err(IntegrityError::InvalidRank(parent));
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a canonical spanning edge list of the partition: one edge for
+    /// every key, in insertion order, to the first key of its set. The first
+    /// key of every set is linked to itself.
+    ///
+    /// Unlike the [parts](Self::into_parts), the edges don't depend on the
+    /// shape of the trees or on the hasher, so equal partitions with keys
+    /// inserted in the same order export the same edges. Collecting the edges
+    /// into a `DisjointHashSet` restores the partition and the order of its
+    /// keys.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d"), ("d", "b"), ("e", "e")]);
+    /// let edges: Vec<(&str, &str)> =
+    ///     djhs.canonical_edges().into_iter().map(|(a, b)| (*a, *b)).collect();
+    /// assert_eq!(edges, vec![("a", "a"), ("b", "a"), ("c", "a"), ("d", "a"), ("e", "e")]);
+    ///
+    /// let restored = DisjointHashSet::from_iter(edges.clone());
+    /// assert_eq!(restored.canonical_edges(), djhs.canonical_edges());
+    /// ```
+    pub fn canonical_edges(&self) -> Vec<(&K, &K)> {
+        let mut firsts = vec![None; self.forest.len()];
+        (0..self.forest.len())
+            .map(PointerId)
+            .map(|id| {
+                let first = *firsts[self.root(id).0].get_or_insert(id);
+                (self.key(id), self.key(first))
+            })
+            .collect()
+    }
+
+    /// Consumes the `DisjointHashSet`, returning its keys and parent table.
+    /// Recorded history, events, proofs and edges are dropped.
+    pub fn into_parts(self) -> Parts<K> {
+        Parts {
+            parents: self.forest.data.iter().map(|pointer| pointer.parent.0).collect(),
+            ranks: self.forest.data.iter().map(|pointer| pointer.rank).collect(),
+            keys: self.keys,
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::dense::DenseDisjointSet;
+
+/// A grid of sites that are opened one by one, tracking whether open sites
+/// connect the top row to the bottom row.
+///
+/// The top and bottom rows are linked to two virtual sentinel sites, so that
+/// [`percolates`](Self::percolates) is a single connectivity query. A second
+/// partition without the bottom sentinel answers [`is_full`](Self::is_full),
+/// so that sites connected to the top only through the bottom sentinel are not
+/// reported as full.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::Percolation;
+///
+/// // Estimate the percolation threshold of a 20x20 grid.
+/// let mut state = 0x9e37_79b9_u64;
+/// let mut random_below = |n: usize| {
+///     state ^= state << 13;
+///     state ^= state >> 7;
+///     state ^= state << 17;
+///     (state % n as u64) as usize
+/// };
+///
+/// let mut grid = Percolation::new(20, 20);
+/// while !grid.percolates() {
+///     grid.open(random_below(20), random_below(20));
+/// }
+/// let threshold = grid.open_count() as f64 / 400.0;
+/// assert!(0.3 < threshold && threshold < 0.9);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Percolation {
+    rows: usize,
+    columns: usize,
+    open: Vec<bool>,
+    open_count: usize,
+    /// The sites, followed by the top and the bottom sentinels.
+    connected: DenseDisjointSet,
+    /// The sites, followed by the top sentinel.
+    full: DenseDisjointSet,
+}
+
+impl Percolation {
+    /// Creates a grid of closed sites.
+    ///
+    /// # Panics
+    /// Panics if the grid has no sites.
+    pub fn new(rows: usize, columns: usize) -> Self {
+        assert!(rows > 0 && columns > 0, "a percolation grid needs at least one site");
+        let sites = rows * columns;
+        Self {
+            rows,
+            columns,
+            open: vec![false; sites],
+            open_count: 0,
+            connected: DenseDisjointSet::new(sites + 2),
+            full: DenseDisjointSet::new(sites + 1),
+        }
+    }
+
+    /// Opens the site, linking it to its open neighbours. Returns true if it
+    /// was closed.
+    ///
+    /// # Panics
+    /// Panics if the site is out of range.
+    pub fn open(&mut self, row: usize, column: usize) -> bool {
+        let site = self.site(row, column);
+        if self.open[site] {
+            return false;
+        }
+        self.open[site] = true;
+        self.open_count += 1;
+
+        let (top, bottom) = (self.open.len(), self.open.len() + 1);
+        if row == 0 {
+            self.connected.union(site, top);
+            self.full.union(site, top);
+        }
+        if row == self.rows - 1 {
+            self.connected.union(site, bottom);
+        }
+
+        let neighbours = [
+            row.checked_sub(1).map(|row| (row, column)),
+            (row + 1 < self.rows).then_some((row + 1, column)),
+            column.checked_sub(1).map(|column| (row, column)),
+            (column + 1 < self.columns).then_some((row, column + 1)),
+        ];
+        for (row, column) in neighbours.into_iter().flatten() {
+            let neighbour = row * self.columns + column;
+            if self.open[neighbour] {
+                self.connected.union(site, neighbour);
+                self.full.union(site, neighbour);
+            }
+        }
+        true
+    }
+
+    /// Checks if the site is open.
+    ///
+    /// # Panics
+    /// Panics if the site is out of range.
+    pub fn is_open(&self, row: usize, column: usize) -> bool {
+        self.open[self.site(row, column)]
+    }
+
+    /// Checks if the site is open and connected to the top row through open
+    /// sites.
+    ///
+    /// # Panics
+    /// Panics if the site is out of range.
+    ///
+    /// ```
+    /// use disjoint_hash_set::Percolation;
+    ///
+    /// let mut grid = Percolation::new(3, 3);
+    /// grid.open(0, 0);
+    /// grid.open(1, 0);
+    /// grid.open(2, 0);
+    /// grid.open(2, 2);
+    /// assert!(grid.percolates());
+    /// assert!(grid.is_full(2, 0));
+    /// assert!(!grid.is_full(2, 2)); // only connected to the bottom
+    /// ```
+    pub fn is_full(&mut self, row: usize, column: usize) -> bool {
+        let site = self.site(row, column);
+        self.full.is_linked(site, self.open.len())
+    }
+
+    /// Checks if open sites connect the top row to the bottom row.
+    pub fn percolates(&mut self) -> bool {
+        self.connected.is_linked(self.open.len(), self.open.len() + 1)
+    }
+
+    /// The number of open sites.
+    pub fn open_count(&self) -> usize {
+        self.open_count
+    }
+
+    fn site(&self, row: usize, column: usize) -> usize {
+        assert!(
+            row < self.rows && column < self.columns,
+            "site ({row}, {column}) out of range for a {}x{} grid",
+            self.rows,
+            self.columns
+        );
+        row * self.columns + column
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    fs::File,
+    hash::{BuildHasher, Hash, RandomState},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::{DisjointHashSet, Parts};
+
+/// The bytes every file written by [`DisjointHashSet::save_to_writer`] starts
+/// with.
+const MAGIC: [u8; 4] = *b"DJHS";
+
+/// The version of the format, bumped on every incompatible change.
+const FORMAT_VERSION: u16 = 1;
+
+/// A key that can be written by [`DisjointHashSet::save_to_writer`] and read
+/// back by [`DisjointHashSet::load_from_reader`].
+///
+/// Implemented for integers, `bool`, `char` and `String`. Integers are written
+/// in little-endian order, `usize` and `isize` as 64 bits so that files are
+/// portable, and strings prefixed by their length in bytes.
+pub trait BinaryKey: Sized {
+    /// Writes the key.
+    fn write_key<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    /// Reads a key written by [`write_key`](Self::write_key).
+    fn read_key<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+macro_rules! impl_binary_key {
+    ($($int:ty),*) => {$(
+        impl BinaryKey for $int {
+            fn write_key<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                writer.write_all(&self.to_le_bytes())
+            }
+
+            fn read_key<R: Read>(reader: &mut R) -> io::Result<Self> {
+                let mut bytes = [0; size_of::<$int>()];
+                reader.read_exact(&mut bytes)?;
+                Ok(<$int>::from_le_bytes(bytes))
+            }
+        }
+    )*};
+}
+
+impl_binary_key!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl BinaryKey for usize {
+    fn write_key<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u64).write_key(writer)
+    }
+
+    fn read_key<R: Read>(reader: &mut R) -> io::Result<Self> {
+        usize::try_from(u64::read_key(reader)?).map_err(|_| invalid_data("usize key out of range"))
+    }
+}
+
+impl BinaryKey for isize {
+    fn write_key<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as i64).write_key(writer)
+    }
+
+    fn read_key<R: Read>(reader: &mut R) -> io::Result<Self> {
+        isize::try_from(i64::read_key(reader)?).map_err(|_| invalid_data("isize key out of range"))
+    }
+}
+
+impl BinaryKey for bool {
+    fn write_key<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        u8::from(*self).write_key(writer)
+    }
+
+    fn read_key<R: Read>(reader: &mut R) -> io::Result<Self> {
+        match u8::read_key(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid_data("invalid bool key")),
+        }
+    }
+}
+
+impl BinaryKey for char {
+    fn write_key<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        u32::from(*self).write_key(writer)
+    }
+
+    fn read_key<R: Read>(reader: &mut R) -> io::Result<Self> {
+        char::from_u32(u32::read_key(reader)?).ok_or_else(|| invalid_data("invalid char key"))
+    }
+}
+
+impl BinaryKey for String {
+    fn write_key<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (self.len() as u64).write_key(writer)?;
+        writer.write_all(self.as_bytes())
+    }
+
+    fn read_key<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let len = u64::read_key(reader)?;
+        let mut bytes = Vec::new();
+        if reader.take(len).read_to_end(&mut bytes)? as u64 != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        String::from_utf8(bytes).map_err(|_| invalid_data("string key isn't UTF-8"))
+    }
+}
+
+impl<K: Eq + Hash + BinaryKey> DisjointHashSet<K, RandomState> {
+    /// Reads a `DisjointHashSet` written by
+    /// [`save_to_writer`](DisjointHashSet::save_to_writer). The structure is
+    /// validated as by [`from_parts`](DisjointHashSet::from_parts).
+    ///
+    /// # Errors
+    /// Fails if reading fails, or with `InvalidData` if the reader doesn't
+    /// start with the magic number, holds a version of the format other than
+    /// the current one, or holds an invalid structure.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let djhs = DisjointHashSet::from_iter(vec![(1u32, 2u32), (3, 4), (2, 5)]);
+    /// let mut bytes = Vec::new();
+    /// djhs.save_to_writer(&mut bytes).unwrap();
+    ///
+    /// let mut loaded = DisjointHashSet::<u32>::load_from_reader(bytes.as_slice()).unwrap();
+    /// assert!(loaded.is_linked(1, 5));
+    /// assert_eq!(loaded.canonical_edges(), djhs.canonical_edges());
+    ///
+    /// // A newer version of the format is refused.
+    /// bytes[4] = 2;
+    /// assert!(DisjointHashSet::<u32>::load_from_reader(bytes.as_slice()).is_err());
+    /// ```
+    pub fn load_from_reader<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("not a saved DisjointHashSet"));
+        }
+        let version = u16::read_key(&mut reader)?;
+        if version != FORMAT_VERSION {
+            return Err(invalid_data(format!("format version {version} is not supported")));
+        }
+
+        let len = usize::read_key(&mut reader)?;
+        // The length is untrusted, so it only bounds the initial allocation.
+        let capacity = len.min(1 << 16);
+        let mut parts = Parts {
+            keys: Vec::with_capacity(capacity),
+            parents: Vec::with_capacity(capacity),
+            ranks: Vec::with_capacity(capacity),
+        };
+        for _ in 0..len {
+            parts.keys.push(K::read_key(&mut reader)?);
+            parts.parents.push(usize::read_key(&mut reader)?);
+            parts.ranks.push(u8::read_key(&mut reader)?);
+        }
+
+        Self::from_parts(parts).map_err(invalid_data)
+    }
+
+    /// Reads a `DisjointHashSet` from a file written by
+    /// [`save_to_path`](DisjointHashSet::save_to_path).
+    ///
+    /// # Errors
+    /// Fails if the file can't be opened, or as by
+    /// [`load_from_reader`](DisjointHashSet::load_from_reader).
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::load_from_reader(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<K: Eq + Hash + BinaryKey, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Writes the structure as is, after a magic number and the version of the
+    /// format, to be read back by
+    /// [`load_from_reader`](DisjointHashSet::load_from_reader). Recorded
+    /// history, events, proofs and edges are not written.
+    pub fn save_to_writer<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&MAGIC)?;
+        FORMAT_VERSION.write_key(&mut writer)?;
+        self.keys.len().write_key(&mut writer)?;
+        for (key, pointer) in self.keys.iter().zip(&self.forest.data) {
+            key.write_key(&mut writer)?;
+            pointer.parent.0.write_key(&mut writer)?;
+            pointer.rank.write_key(&mut writer)?;
+        }
+        writer.flush()
+    }
+
+    /// Writes the structure to a file, replacing it if it exists, as by
+    /// [`save_to_writer`](Self::save_to_writer).
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let path = std::env::temp_dir().join("disjoint-hash-set-save-doctest.djhs");
+    /// let djhs = DisjointHashSet::from_iter(vec![("a".to_string(), "b".to_string())]);
+    /// djhs.save_to_path(&path).unwrap();
+    ///
+    /// let mut loaded = DisjointHashSet::<String>::load_from_path(&path).unwrap();
+    /// assert!(loaded.is_linked("a".to_string(), "b".to_string()));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save_to_path<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.save_to_writer(BufWriter::new(File::create(path)?))
+    }
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
@@ -0,0 +1,438 @@
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, Hash, RandomState},
+    iter::FusedIterator,
+    sync::Arc,
+};
+
+use crate::{DisjointQuery, ParentPointer, PointerId};
+
+/// A `DisjointHashSet` whose storage is shared between snapshots.
+///
+/// Keys and parent pointers are kept in tries of reference counted nodes, so
+/// [`snapshot`](Self::snapshot) is `O(1)` and each snapshot can be queried or
+/// mutated independently. A mutation only copies the nodes on the path to the
+/// entries it touches, and only while those nodes are still shared with
+/// another snapshot.
+///
+/// The structure is fully persistent: alongside the in-place `insert` and
+/// `link`, [`inserted`](Self::inserted) and [`linked`](Self::linked) return a
+/// new version and leave the original untouched.
+///
+/// Path compression is not applied, as it would turn queries into writes.
+/// Rank-based joins alone bound `find` to `O(log n)`.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::PersistentDisjointHashSet;
+///
+/// let mut djhs = PersistentDisjointHashSet::new();
+/// djhs.link("a", "b");
+/// djhs.link("c", "d");
+///
+/// // What if "b" and "c" were the same?
+/// let mut what_if = djhs.snapshot();
+/// what_if.link("b", "c");
+/// assert!(what_if.is_linked("a", "d"));
+/// assert!(!djhs.is_linked("a", "d"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PersistentDisjointHashSet<K, S = RandomState> {
+    keys: PersistentVec<K>,
+    data: PersistentVec<ParentPointer>,
+    index: HashTrie,
+    hash_builder: S,
+}
+
+impl<K: Clone + Eq + Hash> PersistentDisjointHashSet<K, RandomState> {
+    /// Creates an empty `PersistentDisjointHashSet`.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::PersistentDisjointHashSet;
+    /// let mut djhs: PersistentDisjointHashSet<&str> = PersistentDisjointHashSet::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Clone + Eq + Hash> Default for PersistentDisjointHashSet<K, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + Eq + Hash, S: Clone + BuildHasher> PersistentDisjointHashSet<K, S> {
+    /// Creates an empty `PersistentDisjointHashSet` which will use the given
+    /// hash builder to hash keys.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::hash::RandomState;
+    /// use disjoint_hash_set::PersistentDisjointHashSet;
+    /// let s = RandomState::new();
+    /// let mut djhs: PersistentDisjointHashSet<&str> = PersistentDisjointHashSet::with_hasher(s);
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            keys: PersistentVec::new(),
+            data: PersistentVec::new(),
+            index: HashTrie::default(),
+            hash_builder,
+        }
+    }
+
+    /// Returns an independent copy of the structure in `O(1)`, sharing all of
+    /// its storage with `self` until either of them is mutated.
+    ///
+    /// ```
+    /// use disjoint_hash_set::PersistentDisjointHashSet;
+    ///
+    /// let mut djhs = PersistentDisjointHashSet::new();
+    /// djhs.insert("a");
+    ///
+    /// let snapshot = djhs.snapshot();
+    /// djhs.link("a", "b");
+    /// assert!(djhs.contains("b"));
+    /// assert!(!snapshot.contains("b"));
+    /// ```
+    pub fn snapshot(&self) -> Self {
+        self.clone()
+    }
+
+    /// Check if the value has already been inserted.
+    ///
+    /// ```
+    /// use disjoint_hash_set::PersistentDisjointHashSet;
+    ///
+    /// let mut djhs = PersistentDisjointHashSet::new();
+    /// assert!(!djhs.contains(&"a"));
+    /// djhs.insert("a");
+    /// assert!(djhs.contains(&"a"));
+    /// ```
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.id(val.borrow()).is_some()
+    }
+
+    /// Insert the value as a new disjoint set with a single member. Returns
+    /// true if the value was not already present.
+    ///
+    /// ```
+    /// use disjoint_hash_set::PersistentDisjointHashSet;
+    ///
+    /// let mut djhs = PersistentDisjointHashSet::new();
+    /// assert!(djhs.insert("a"));
+    /// assert!(!djhs.insert("a"));
+    /// ```
+    pub fn insert(&mut self, val: K) -> bool {
+        (!self.contains(&val)).then(|| self.insert_unchecked(val)).is_some()
+    }
+
+    /// Checks if the two keys are members of the same set.
+    /// This will not implicitly add values that were not already present.
+    ///
+    /// ```
+    /// use disjoint_hash_set::PersistentDisjointHashSet;
+    ///
+    /// let mut djhs = PersistentDisjointHashSet::new();
+    /// djhs.link("a", "b");
+    /// assert!(djhs.is_linked("b", "a"));
+    /// assert!(!djhs.is_linked("a", "c"));
+    /// ```
+    pub fn is_linked<T: Borrow<K>>(&self, val1: T, val2: T) -> bool {
+        let (id1, id2) = (
+            self.id(val1.borrow()).map(|id| self.find(id)),
+            self.id(val2.borrow()).map(|id| self.find(id)),
+        );
+
+        id1.is_some() && id2.is_some() && id1 == id2
+    }
+
+    /// Link the respective sets of the two provided values. This will insert
+    /// non-existent values in the process.
+    ///
+    /// ```
+    /// use disjoint_hash_set::PersistentDisjointHashSet;
+    ///
+    /// let mut djhs = PersistentDisjointHashSet::new();
+    /// djhs.link("a", "b");
+    /// assert!(djhs.contains("a"));
+    /// assert!(djhs.is_linked("a", "b"));
+    /// ```
+    pub fn link(&mut self, val1: K, val2: K) {
+        let ids = (self.id_or_insert(val1), self.id_or_insert(val2));
+        let roots = (self.find(ids.0), self.find(ids.1));
+
+        if roots.0 != roots.1 {
+            let ranks = (self.data.get(roots.0.0).rank, self.data.get(roots.1.0).rank);
+            let (root, child) =
+                if ranks.0 < ranks.1 { (roots.1, roots.0) } else { (roots.0, roots.1) };
+
+            let child_size = self.data.get(child.0).size;
+            self.data.get_mut(child.0).parent = root;
+            self.data.get_mut(root.0).size += child_size;
+            if ranks.0 == ranks.1 {
+                self.data.get_mut(root.0).rank += 1;
+            }
+        }
+    }
+
+    /// Returns a new version of the structure with the value inserted, leaving
+    /// `self` untouched. Both versions share all unmodified storage.
+    ///
+    /// ```
+    /// use disjoint_hash_set::PersistentDisjointHashSet;
+    ///
+    /// let v0 = PersistentDisjointHashSet::new();
+    /// let v1 = v0.inserted("a");
+    /// assert!(!v0.contains("a"));
+    /// assert!(v1.contains("a"));
+    /// ```
+    #[must_use]
+    pub fn inserted(&self, val: K) -> Self {
+        let mut next = self.snapshot();
+        next.insert(val);
+        next
+    }
+
+    /// Returns a new version of the structure with the respective sets of the
+    /// two values linked, leaving `self` untouched. Both versions share all
+    /// unmodified storage, so any number of historical versions can be kept
+    /// alive cheaply.
+    ///
+    /// ```
+    /// use disjoint_hash_set::PersistentDisjointHashSet;
+    ///
+    /// let v0 = PersistentDisjointHashSet::new().linked("a", "b");
+    /// let v1 = v0.linked("b", "c");
+    /// let v2 = v1.linked("x", "y");
+    ///
+    /// assert!(!v0.is_linked("a", "c"));
+    /// assert!(v1.is_linked("a", "c"));
+    /// assert!(!v1.contains("x"));
+    /// assert!(v2.is_linked("x", "y"));
+    /// ```
+    #[must_use]
+    pub fn linked(&self, val1: K, val2: K) -> Self {
+        let mut next = self.snapshot();
+        next.link(val1, val2);
+        next
+    }
+
+    /// Returns an iterator of HashSets for each disjoint set, cloning the keys
+    /// so that `self` remains available.
+    ///
+    /// ```
+    /// use disjoint_hash_set::PersistentDisjointHashSet;
+    ///
+    /// let mut djhs = PersistentDisjointHashSet::new();
+    /// djhs.link("a", "b");
+    /// djhs.insert("c");
+    /// assert_eq!(djhs.sets().count(), 2);
+    /// ```
+    pub fn sets(&self) -> impl ExactSizeIterator<Item = HashSet<K>> + FusedIterator {
+        let mut sets = HashMap::new();
+
+        (0..self.keys.len()).for_each(|id| {
+            let root = self.find(PointerId(id));
+            sets.entry(root).or_insert_with(HashSet::new).insert(self.keys.get(id).clone());
+        });
+
+        sets.into_values()
+    }
+
+    fn find(&self, mut id: PointerId) -> PointerId {
+        while self.data.get(id.0).parent != id {
+            id = self.data.get(id.0).parent;
+        }
+        id
+    }
+
+    fn id(&self, value: &K) -> Option<PointerId> {
+        let hash = self.hash_builder.hash_one(value);
+        self.index.get(hash).iter().copied().find(|id| self.keys.get(id.0) == value)
+    }
+
+    fn id_or_insert(&mut self, value: K) -> PointerId {
+        self.id(&value).unwrap_or_else(|| self.insert_unchecked(value))
+    }
+
+    fn insert_unchecked(&mut self, value: K) -> PointerId {
+        let id = PointerId(self.keys.len());
+        self.index.insert(self.hash_builder.hash_one(&value), id);
+        self.keys.push(value);
+        self.data.push(ParentPointer { parent: id, rank: 0, size: 1 });
+        id
+    }
+}
+
+impl<K: Clone + Eq + Hash, S: Clone + BuildHasher> DisjointQuery<K>
+    for PersistentDisjointHashSet<K, S>
+{
+    fn contains(&self, key: &K) -> bool {
+        PersistentDisjointHashSet::contains(self, key)
+    }
+
+    fn is_linked(&self, key1: &K, key2: &K) -> bool {
+        PersistentDisjointHashSet::is_linked(self, key1, key2)
+    }
+
+    fn representative(&self, key: &K) -> Option<K> {
+        self.id(key).map(|id| self.keys.get(self.find(id).0).clone())
+    }
+
+    fn set_size(&self, key: &K) -> Option<usize> {
+        self.id(key).map(|id| self.data.get(self.find(id).0).size)
+    }
+}
+
+const BITS: usize = 5;
+const WIDTH: usize = 1 << BITS;
+const MASK: usize = WIDTH - 1;
+
+/// A vector stored as a trie of `WIDTH`-ary nodes, each reference counted so
+/// that clones share every node until it is written to.
+#[derive(Debug, Clone)]
+struct PersistentVec<T> {
+    root: Arc<VecNode<T>>,
+    len: usize,
+    shift: usize,
+}
+
+#[derive(Debug, Clone)]
+enum VecNode<T> {
+    Leaf(Vec<T>),
+    Branch(Vec<Arc<VecNode<T>>>),
+}
+
+impl<T: Clone> PersistentVec<T> {
+    fn new() -> Self {
+        Self { root: Arc::new(VecNode::Leaf(Vec::new())), len: 0, shift: 0 }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, index: usize) -> &T {
+        let (mut node, mut shift) = (&*self.root, self.shift);
+        loop {
+            match node {
+                VecNode::Leaf(values) => return &values[index & MASK],
+                VecNode::Branch(children) => node = &children[(index >> shift) & MASK],
+            }
+            shift -= BITS;
+        }
+    }
+
+    fn get_mut(&mut self, index: usize) -> &mut T {
+        let (mut node, mut shift) = (Arc::make_mut(&mut self.root), self.shift);
+        loop {
+            match node {
+                VecNode::Leaf(values) => return &mut values[index & MASK],
+                VecNode::Branch(children) => {
+                    node = Arc::make_mut(&mut children[(index >> shift) & MASK])
+                }
+            }
+            shift -= BITS;
+        }
+    }
+
+    fn push(&mut self, value: T) {
+        if self.len == WIDTH << self.shift {
+            let root = std::mem::replace(&mut self.root, Arc::new(VecNode::Branch(Vec::new())));
+            self.root = Arc::new(VecNode::Branch(vec![root, Self::path(self.shift)]));
+            self.shift += BITS;
+        }
+
+        let (mut node, mut shift) = (Arc::make_mut(&mut self.root), self.shift);
+        loop {
+            match node {
+                VecNode::Leaf(values) => break values.push(value),
+                VecNode::Branch(children) => {
+                    let child = (self.len >> shift) & MASK;
+                    if child == children.len() {
+                        children.push(Self::path(shift - BITS));
+                    }
+                    node = Arc::make_mut(&mut children[child]);
+                }
+            }
+            shift -= BITS;
+        }
+        self.len += 1;
+    }
+
+    /// An empty chain of nodes reaching down to a leaf from height `shift`.
+    fn path(shift: usize) -> Arc<VecNode<T>> {
+        if shift == 0 {
+            Arc::new(VecNode::Leaf(Vec::with_capacity(WIDTH)))
+        } else {
+            Arc::new(VecNode::Branch(vec![Self::path(shift - BITS)]))
+        }
+    }
+}
+
+const HASH_BITS: u32 = 4;
+const HASH_MASK: u64 = (1 << HASH_BITS) - 1;
+
+/// A hash array mapped trie from key hashes to the ids of keys with that
+/// hash, sharing nodes between clones in the same way as `PersistentVec`.
+#[derive(Debug, Clone, Default)]
+struct HashTrie {
+    root: Option<Arc<HashNode>>,
+}
+
+#[derive(Debug, Clone)]
+enum HashNode {
+    Leaf(u64, Vec<PointerId>),
+    Branch(Vec<Option<Arc<HashNode>>>),
+}
+
+impl HashTrie {
+    fn get(&self, hash: u64) -> &[PointerId] {
+        let (mut slot, mut depth) = (&self.root, 0);
+        while let Some(node) = slot {
+            match &**node {
+                HashNode::Leaf(h, ids) => return if *h == hash { ids } else { &[] },
+                HashNode::Branch(children) => slot = &children[Self::digit(hash, depth)],
+            }
+            depth += 1;
+        }
+        &[]
+    }
+
+    fn insert(&mut self, hash: u64, id: PointerId) {
+        let (mut slot, mut depth) = (&mut self.root, 0);
+        loop {
+            let Some(node) = slot else {
+                *slot = Some(Arc::new(HashNode::Leaf(hash, vec![id])));
+                return;
+            };
+
+            let node = Arc::make_mut(node);
+            if let HashNode::Leaf(leaf_hash, ids) = node {
+                if *leaf_hash == hash {
+                    ids.push(id);
+                    return;
+                }
+
+                // Push the existing leaf one level down and continue from there.
+                let digit = Self::digit(*leaf_hash, depth);
+                let leaf = std::mem::replace(node, HashNode::Branch(vec![None; 1 << HASH_BITS]));
+                if let HashNode::Branch(children) = node {
+                    children[digit] = Some(Arc::new(leaf));
+                }
+            }
+
+            let HashNode::Branch(children) = node else { unreachable!() };
+            slot = &mut children[Self::digit(hash, depth)];
+            depth += 1;
+        }
+    }
+
+    fn digit(hash: u64, depth: u32) -> usize {
+        ((hash >> (depth * HASH_BITS)) & HASH_MASK) as usize
+    }
+}
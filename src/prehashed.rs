@@ -0,0 +1,97 @@
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/// A key along with a hash computed for it upstream, so that a
+/// `DisjointHashSet` with a [`PreHashedState`] uses that hash instead of
+/// hashing the key again.
+///
+/// Two `PreHashed` keys are equal if both their hashes and their keys are, so
+/// the hash must be computed the same way for every key: equal keys with
+/// different hashes are treated as distinct. The hash should be of good
+/// quality, as the structure uses it as is.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{DisjointHashSet, PreHashed, PreHashedState};
+/// use std::hash::{BuildHasher, RandomState};
+///
+/// // Hashes computed once, such as by a dedup stage of a pipeline.
+/// let state = RandomState::new();
+/// let prehash = |doc: &'static [u8]| PreHashed::new(state.hash_one(doc), doc);
+///
+/// let mut djhs = DisjointHashSet::with_hasher(PreHashedState);
+/// djhs.link(prehash(b"long document a"), prehash(b"long document b"));
+/// djhs.insert(prehash(b"long document c"));
+///
+/// assert!(djhs.is_linked(&prehash(b"long document b"), &prehash(b"long document a")));
+/// assert!(!djhs.contains(&prehash(b"long document d")));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PreHashed<K> {
+    hash: u64,
+    key: K,
+}
+
+impl<K> PreHashed<K> {
+    /// Pairs the key with its hash.
+    pub fn new(hash: u64, key: K) -> Self {
+        Self { hash, key }
+    }
+
+    /// The hash of the key.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns a reference to the key.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Consumes the `PreHashed`, returning the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+}
+
+impl<K> Hash for PreHashed<K> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+/// A `BuildHasher` passing the hash of a [`PreHashed`] key through as is.
+///
+/// Other keys are hashed poorly, as their bits are only folded together.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PreHashedState;
+
+impl BuildHasher for PreHashedState {
+    type Hasher = PreHashedHasher;
+
+    fn build_hasher(&self) -> PreHashedHasher {
+        PreHashedHasher(0)
+    }
+}
+
+/// The hasher created by a [`PreHashedState`].
+#[derive(Debug, Clone, Copy)]
+pub struct PreHashedHasher(u64);
+
+impl Hasher for PreHashedHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        // A single write, that of a `PreHashed` key, leaves the hash as is.
+        self.0 = self.0.rotate_left(26) ^ i;
+    }
+}
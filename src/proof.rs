@@ -0,0 +1,117 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{DisjointHashSet, PointerId, forest::Forest};
+
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Starts maintaining a proof forest from which
+    /// [`why_linked`](Self::why_linked) can explain connectivity.
+    ///
+    /// The proof forest holds, for every effective union, the pair of keys
+    /// that was passed to `link`, arranged so that the path between two keys
+    /// is made up of exactly those pairs. Maintaining it costs one pointer per
+    /// key and amortized `O(log n)` per union. Unions performed before
+    /// recording started cannot be explained.
+    pub fn record_proofs(&mut self) {
+        if self.forest.proofs.is_none() {
+            self.forest.proofs = Some(vec![None; self.forest.len()]);
+        }
+    }
+
+    /// Returns a chain of linked pairs connecting `val1` to `val2`, each
+    /// oriented along the chain, or `None` if the keys are not linked or
+    /// their connection was established before proofs were recorded.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.record_proofs();
+    /// djhs.link("alice@example.com", "555-0100");
+    /// djhs.link("bob@example.com", "device-7");
+    /// djhs.link("device-7", "555-0100");
+    /// djhs.link("alice@example.com", "device-7"); // already linked
+    ///
+    /// assert_eq!(
+    ///     djhs.why_linked("alice@example.com", "bob@example.com"),
+    ///     Some(vec![
+    ///         (&"alice@example.com", &"555-0100"),
+    ///         (&"555-0100", &"device-7"),
+    ///         (&"device-7", &"bob@example.com"),
+    ///     ])
+    /// );
+    /// assert_eq!(djhs.why_linked("alice@example.com", "carol@example.com"), None);
+    /// ```
+    pub fn why_linked<T: Borrow<K>>(&self, val1: T, val2: T) -> Option<Vec<(&K, &K)>> {
+        let proofs = self.forest.proofs.as_ref()?;
+        let (id1, id2) = (self.id(val1.borrow())?, self.id(val2.borrow())?);
+
+        let mut ancestors = HashMap::new();
+        let mut id = Some(id1);
+        while let Some(ancestor) = id {
+            ancestors.insert(ancestor, ancestors.len());
+            id = proofs[ancestor.0];
+        }
+
+        let (mut from_val2, mut id) = (Vec::new(), id2);
+        let meet = loop {
+            if let Some(&depth) = ancestors.get(&id) {
+                break depth;
+            }
+            let parent = proofs[id.0]?;
+            from_val2.push((self.key(parent), self.key(id)));
+            id = parent;
+        };
+
+        let mut chain = Vec::with_capacity(meet + from_val2.len());
+        let mut id = id1;
+        for _ in 0..meet {
+            let parent = proofs[id.0].expect("ancestors have parents");
+            chain.push((self.key(id), self.key(parent)));
+            id = parent;
+        }
+        chain.extend(from_val2.into_iter().rev());
+        Some(chain)
+    }
+}
+
+impl Forest {
+    /// Records the union of the sets containing `id1` and `id2`, of the given
+    /// sizes, in the proof forest. Returns the added edge as a child and its
+    /// proof parent.
+    pub(crate) fn prove(
+        &mut self,
+        id1: PointerId,
+        id2: PointerId,
+        sizes: (usize, usize),
+    ) -> Option<(PointerId, PointerId)> {
+        let proofs = self.proofs.as_mut()?;
+
+        // Reroot the smaller proof tree at its linked key and hang it beneath
+        // the other linked key.
+        let (child, parent) = if sizes.0 <= sizes.1 { (id1, id2) } else { (id2, id1) };
+        let (mut previous, mut id) = (None, Some(child));
+        while let Some(current) = id {
+            id = std::mem::replace(&mut proofs[current.0], previous);
+            previous = Some(current);
+        }
+
+        proofs[child.0] = Some(parent);
+        Some((child, parent))
+    }
+
+    /// Removes an edge of the proof forest for a union that is being undone.
+    /// Later rerooting may have reversed the edge.
+    pub(crate) fn unprove(&mut self, (id1, id2): (PointerId, PointerId)) {
+        if let Some(proofs) = &mut self.proofs {
+            if proofs[id1.0] == Some(id2) {
+                proofs[id1.0] = None;
+            } else if proofs[id2.0] == Some(id1) {
+                proofs[id2.0] = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::hash::{BuildHasher, Hash};
+
+use crate::DisjointHashSet;
+
+/// The read side of a disjoint set, implemented by [`DisjointHashSet`],
+/// [`PersistentDisjointHashSet`](crate::PersistentDisjointHashSet) and
+/// [`SharedDisjointHashSet`](crate::SharedDisjointHashSet).
+///
+/// The trait is object safe, so that code can hold a
+/// `Box<dyn DisjointQuery<K>>` and swap implementations. Queries take `&self`
+/// and don't compress paths, and representatives are returned as clones, as
+/// a shared structure can't lend out its keys.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{
+///     DisjointHashSet, DisjointQuery, PersistentDisjointHashSet, SharedDisjointHashSet,
+/// };
+///
+/// let edges = vec![("a", "b"), ("b", "c"), ("d", "e")];
+/// let mut persistent = PersistentDisjointHashSet::new();
+/// let shared = SharedDisjointHashSet::new();
+/// for &(a, b) in &edges {
+///     persistent.link(a, b);
+///     shared.link(a, b);
+/// }
+///
+/// let queries: Vec<Box<dyn DisjointQuery<&str>>> =
+///     vec![Box::new(DisjointHashSet::from_iter(edges)), Box::new(persistent), Box::new(shared)];
+/// for query in &queries {
+///     assert!(query.is_linked(&"a", &"c"));
+///     assert!(!query.is_linked(&"a", &"d"));
+///     assert_eq!(query.set_size(&"e"), Some(2));
+///     assert_eq!(query.representative(&"c"), query.representative(&"a"));
+/// }
+/// ```
+pub trait DisjointQuery<K> {
+    /// Check if the key has already been inserted.
+    fn contains(&self, key: &K) -> bool;
+
+    /// Checks if the two keys are members of the same set.
+    fn is_linked(&self, key1: &K, key2: &K) -> bool;
+
+    /// Returns the representative of the key's set, or `None` if the key is
+    /// not present.
+    fn representative(&self, key: &K) -> Option<K>;
+
+    /// The number of members of the key's set, or `None` if the key is not
+    /// present.
+    fn set_size(&self, key: &K) -> Option<usize>;
+}
+
+impl<K: Eq + Hash + Clone, S: BuildHasher> DisjointQuery<K> for DisjointHashSet<K, S> {
+    fn contains(&self, key: &K) -> bool {
+        DisjointHashSet::contains(self, key)
+    }
+
+    fn is_linked(&self, key1: &K, key2: &K) -> bool {
+        match (self.id(key1), self.id(key2)) {
+            (Some(id1), Some(id2)) => self.root(id1) == self.root(id2),
+            _ => false,
+        }
+    }
+
+    fn representative(&self, key: &K) -> Option<K> {
+        DisjointHashSet::representative(self, key).cloned()
+    }
+
+    fn set_size(&self, key: &K) -> Option<usize> {
+        DisjointHashSet::set_size(self, key)
+    }
+}
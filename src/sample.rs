@@ -0,0 +1,106 @@
+use std::{
+    borrow::Borrow,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{DisjointHashSet, PointerId};
+
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Draws up to `k` members of the value's set uniformly at random, without
+    /// replacement, or none if the value is not present. The keys are scanned
+    /// once by reservoir sampling, so only the sample is held in memory.
+    ///
+    /// `random_below(n)` must return a uniformly random number below `n`, e.g.
+    /// from the caller's own random number generator.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut state = 0x2545_f491_u64;
+    /// let mut random_below = |n: usize| {
+    ///     state ^= state << 13;
+    ///     state ^= state >> 7;
+    ///     state ^= state << 17;
+    ///     (state % n as u64) as usize
+    /// };
+    ///
+    /// let djhs = DisjointHashSet::from_iter((0..100).map(|i| (i, i % 3)));
+    /// let sample = djhs.sample_members(&4, 5, &mut random_below);
+    /// assert_eq!(sample.len(), 5);
+    /// assert!(sample.iter().all(|&&member| member % 3 == 1));
+    /// assert!(djhs.sample_members(&100, 5, &mut random_below).is_empty());
+    /// ```
+    pub fn sample_members<T, R>(&self, val: T, k: usize, mut random_below: R) -> Vec<&K>
+    where
+        T: Borrow<K>,
+        R: FnMut(usize) -> usize,
+    {
+        let Some(root) = self.id(val.borrow()).map(|id| self.root(id)) else {
+            return Vec::new();
+        };
+
+        let (mut sample, mut seen) = (Vec::with_capacity(k), 0);
+        for id in (0..self.forest.len()).map(PointerId).filter(|&id| self.root(id) == root) {
+            reservoir(&mut sample, k, &mut seen, self.key(id), &mut random_below);
+        }
+        sample
+    }
+
+    /// Draws up to `k` members of every set uniformly at random, without
+    /// replacement, as by [`sample_members`](Self::sample_members), in a
+    /// single scan of the keys. Sets are ordered by their first keys.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut state = 0x2545_f491_u64;
+    /// let random_below = |n: usize| {
+    ///     state ^= state << 13;
+    ///     state ^= state >> 7;
+    ///     state ^= state << 17;
+    ///     (state % n as u64) as usize
+    /// };
+    ///
+    /// let mut djhs = DisjointHashSet::from_iter((0..100).map(|i| (i, i % 3)));
+    /// djhs.insert(100);
+    /// let samples = djhs.sample_per_set(2, random_below);
+    /// assert_eq!(samples.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 2, 2, 1]);
+    /// ```
+    pub fn sample_per_set<R>(&self, k: usize, mut random_below: R) -> Vec<Vec<&K>>
+    where
+        R: FnMut(usize) -> usize,
+    {
+        let mut slots = vec![None; self.forest.len()];
+        let mut samples: Vec<(Vec<&K>, usize)> = Vec::new();
+        for id in (0..self.forest.len()).map(PointerId) {
+            let slot = *slots[self.root(id).0].get_or_insert_with(|| {
+                samples.push((Vec::new(), 0));
+                samples.len() - 1
+            });
+            let (sample, seen) = &mut samples[slot];
+            reservoir(sample, k, seen, self.key(id), &mut random_below);
+        }
+        samples.into_iter().map(|(sample, _)| sample).collect()
+    }
+}
+
+/// Offers the next of `seen` items to a uniform sample of up to `k` of them.
+fn reservoir<'a, K, R>(
+    sample: &mut Vec<&'a K>,
+    k: usize,
+    seen: &mut usize,
+    key: &'a K,
+    random_below: &mut R,
+) where
+    R: FnMut(usize) -> usize,
+{
+    *seen += 1;
+    if sample.len() < k {
+        sample.push(key);
+    } else {
+        let index = random_below(*seen);
+        if index < k {
+            sample[index] = key;
+        }
+    }
+}
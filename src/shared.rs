@@ -0,0 +1,197 @@
+use std::{
+    borrow::Borrow,
+    hash::{BuildHasher, Hash, RandomState},
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use crate::{DisjointHashSet, DisjointQuery};
+
+/// A `DisjointHashSet` behind an `Arc<RwLock<..>>`, shared between threads.
+///
+/// Clones share the same partition. Links take the write lock, while queries
+/// such as [`is_linked`](Self::is_linked) take the read lock and skip path
+/// compression, so that any number of threads can query at once.
+///
+/// A panic in a thread holding the lock doesn't poison the wrapper for every
+/// other thread. Operations of a `DisjointHashSet` leave it consistent unless
+/// hashing or comparing a key panics, so the lock is recovered instead.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::SharedDisjointHashSet;
+/// use std::thread;
+///
+/// let djhs = SharedDisjointHashSet::new();
+/// let handles: Vec<_> = (0..4)
+///     .map(|t| {
+///         let djhs = djhs.clone();
+///         thread::spawn(move || (0..100).for_each(|i| djhs.link(t * 100 + i, t * 100)))
+///     })
+///     .collect();
+/// handles.into_iter().for_each(|handle| handle.join().unwrap());
+///
+/// assert!(djhs.is_linked(&199, &101));
+/// assert_eq!(djhs.set_count(), 4);
+/// ```
+#[derive(Debug)]
+pub struct SharedDisjointHashSet<K, S = RandomState> {
+    inner: Arc<RwLock<DisjointHashSet<K, S>>>,
+}
+
+impl<K: Eq + Hash> SharedDisjointHashSet<K, RandomState> {
+    /// Creates an empty `SharedDisjointHashSet`.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::SharedDisjointHashSet;
+    /// let djhs: SharedDisjointHashSet<&str> = SharedDisjointHashSet::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::from(DisjointHashSet::new())
+    }
+}
+
+impl<K: Eq + Hash> Default for SharedDisjointHashSet<K, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, S> Clone for SharedDisjointHashSet<K, S> {
+    /// Returns a handle to the same partition.
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<K, S> From<DisjointHashSet<K, S>> for SharedDisjointHashSet<K, S> {
+    fn from(djhs: DisjointHashSet<K, S>) -> Self {
+        Self { inner: Arc::new(RwLock::new(djhs)) }
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> SharedDisjointHashSet<K, S> {
+    /// Creates an empty `SharedDisjointHashSet` which will use the given hash
+    /// builder to hash keys.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::from(DisjointHashSet::with_hasher(hash_builder))
+    }
+
+    /// Check if the value has already been inserted.
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.read().contains(val)
+    }
+
+    /// Insert the value as a new disjoint set with a single member. Returns
+    /// true if the value was not already present.
+    pub fn insert(&self, val: K) -> bool {
+        self.write().insert(val)
+    }
+
+    /// Checks if the two keys are members of the same set.
+    /// This will not implicitly add values that were not already present.
+    pub fn is_linked<T: Borrow<K>>(&self, val1: T, val2: T) -> bool {
+        let djhs = self.read();
+        match (djhs.id(val1.borrow()), djhs.id(val2.borrow())) {
+            (Some(id1), Some(id2)) => djhs.root(id1) == djhs.root(id2),
+            _ => false,
+        }
+    }
+
+    /// The number of members of the value's set, or `None` if the value is not
+    /// present.
+    pub fn set_size<T: Borrow<K>>(&self, val: T) -> Option<usize> {
+        self.read().set_size(val)
+    }
+
+    /// Link the respective sets of the two provided values. This will insert
+    /// non-existent values in the process.
+    pub fn link(&self, val1: K, val2: K) {
+        self.write().link(val1, val2)
+    }
+
+    /// The number of disjoint sets.
+    pub fn set_count(&self) -> usize {
+        self.read().set_count()
+    }
+
+    /// Returns a copy of every disjoint set as a Vec of its members, as by
+    /// [`DisjointHashSet::ordered_sets`]. Links made by other threads while
+    /// the copy is taken wait for it to finish.
+    ///
+    /// ```
+    /// use disjoint_hash_set::SharedDisjointHashSet;
+    ///
+    /// let djhs = SharedDisjointHashSet::new();
+    /// djhs.link("a", "b");
+    /// djhs.insert("c");
+    /// assert_eq!(djhs.snapshot_sets(), vec![vec!["a", "b"], vec!["c"]]);
+    /// ```
+    pub fn snapshot_sets(&self) -> Vec<Vec<K>>
+    where
+        K: Clone,
+    {
+        let djhs = self.read();
+        djhs.grouped_ids()
+            .into_iter()
+            .map(|ids| ids.into_iter().map(|id| djhs.key(id).clone()).collect())
+            .collect()
+    }
+
+    /// Runs `f` with shared access to the underlying `DisjointHashSet`.
+    pub fn with_read<R, F: FnOnce(&DisjointHashSet<K, S>) -> R>(&self, f: F) -> R {
+        f(&self.read())
+    }
+
+    /// Runs `f` with exclusive access to the underlying `DisjointHashSet`, for
+    /// a sequence of operations that other threads must not interleave with.
+    ///
+    /// The sequence is not atomic with respect to panics: if `f` panics, the
+    /// operations it completed stay applied, and the lock is recovered as
+    /// after any other panic, so other threads see the partial sequence.
+    ///
+    /// ```
+    /// use disjoint_hash_set::SharedDisjointHashSet;
+    ///
+    /// let djhs = SharedDisjointHashSet::new();
+    /// let summary = djhs.with_write(|djhs| djhs.link_all(vec![(1, 2), (2, 3), (3, 1)]));
+    /// assert_eq!(summary.redundant, 1);
+    /// ```
+    pub fn with_write<R, F: FnOnce(&mut DisjointHashSet<K, S>) -> R>(&self, f: F) -> R {
+        f(&mut self.write())
+    }
+
+    /// Returns the underlying `DisjointHashSet` if this is the only handle to
+    /// it, or the handle otherwise.
+    pub fn try_into_inner(self) -> Result<DisjointHashSet<K, S>, Self> {
+        Arc::try_unwrap(self.inner)
+            .map(|lock| lock.into_inner().unwrap_or_else(PoisonError::into_inner))
+            .map_err(|inner| Self { inner })
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, DisjointHashSet<K, S>> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, DisjointHashSet<K, S>> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<K: Eq + Hash + Clone, S: BuildHasher> DisjointQuery<K> for SharedDisjointHashSet<K, S> {
+    fn contains(&self, key: &K) -> bool {
+        SharedDisjointHashSet::contains(self, key)
+    }
+
+    fn is_linked(&self, key1: &K, key2: &K) -> bool {
+        SharedDisjointHashSet::is_linked(self, key1, key2)
+    }
+
+    fn representative(&self, key: &K) -> Option<K> {
+        self.read().representative(key).cloned()
+    }
+
+    fn set_size(&self, key: &K) -> Option<usize> {
+        self.read().set_size(key)
+    }
+}
@@ -0,0 +1,92 @@
+/// A fixed range of slots `0..len` that can be erased, answering which slot is
+/// the first free one at or after a given index.
+///
+/// Every erased slot points towards its successor, and lookups compress the
+/// pointers they follow, so that [`find_next_free`](Self::find_next_free)
+/// costs amortized near-constant time. This is the structure behind slot
+/// allocation, or scheduling jobs into the earliest free time slot.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::SuccessorDisjointSet;
+///
+/// // Allocate each request the first free slot at or after its preference.
+/// let mut slots = SuccessorDisjointSet::new(5);
+/// for preferred in [1, 1, 3, 1] {
+///     let slot = slots.find_next_free(preferred).unwrap();
+///     slots.erase(slot);
+/// }
+/// assert_eq!(slots.find_next_free(0), Some(0));
+/// assert_eq!(slots.find_next_free(1), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuccessorDisjointSet {
+    /// For every slot, itself if it is free or a later slot otherwise. The
+    /// final entry is a sentinel that is never erased.
+    next: Vec<usize>,
+}
+
+impl SuccessorDisjointSet {
+    /// Creates `len` free slots.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::SuccessorDisjointSet;
+    /// let slots = SuccessorDisjointSet::new(16);
+    /// assert_eq!(slots.len(), 16);
+    /// ```
+    pub fn new(len: usize) -> Self {
+        Self { next: (0..=len).collect() }
+    }
+
+    /// The number of slots, free or erased.
+    pub fn len(&self) -> usize {
+        self.next.len() - 1
+    }
+
+    /// Checks if there are no slots at all.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if the slot is in range and has not been erased.
+    pub fn is_free(&self, slot: usize) -> bool {
+        slot < self.len() && self.next[slot] == slot
+    }
+
+    /// Erases the slot. Returns true if it was free.
+    ///
+    /// # Panics
+    /// Panics if `slot` is out of range.
+    pub fn erase(&mut self, slot: usize) -> bool {
+        assert!(slot < self.len(), "slot {slot} out of range for {} slots", self.len());
+        let free = self.next[slot] == slot;
+        if free {
+            self.next[slot] = slot + 1;
+        }
+        free
+    }
+
+    /// Returns the first free slot at or after `slot`, or `None` if every
+    /// slot from there on has been erased.
+    ///
+    /// ```
+    /// use disjoint_hash_set::SuccessorDisjointSet;
+    ///
+    /// let mut slots = SuccessorDisjointSet::new(4);
+    /// slots.erase(1);
+    /// slots.erase(2);
+    /// assert_eq!(slots.find_next_free(1), Some(3));
+    /// assert_eq!(slots.find_next_free(4), None);
+    /// ```
+    pub fn find_next_free(&mut self, slot: usize) -> Option<usize> {
+        let mut slot = slot.min(self.len());
+        // Path halving: point every other slot visited at its grandparent.
+        while self.next[slot] != slot {
+            let grandparent = self.next[self.next[slot]];
+            self.next[slot] = grandparent;
+            slot = grandparent;
+        }
+        (slot < self.len()).then_some(slot)
+    }
+}
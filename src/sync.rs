@@ -0,0 +1,136 @@
+use std::{
+    collections::HashSet,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{DisjointHashSet, events::LoggedEvent};
+
+/// A point in the event log of a `DisjointHashSet`, obtained from
+/// [`DisjointHashSet::sync_point`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncPoint {
+    events: usize,
+    epoch: u64,
+}
+
+/// The keys inserted and sets joined by a `DisjointHashSet` since a
+/// [`SyncPoint`], as returned by [`DisjointHashSet::delta_since`].
+///
+/// Applying a delta links its pairs, so deltas can be applied in any order and
+/// any number of times with the same result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delta<K> {
+    /// Inserted keys that are not part of any of the links.
+    pub keys: Vec<K>,
+    /// A pair of keys for every union, in the order they were performed.
+    pub links: Vec<(K, K)>,
+}
+
+impl<K> Delta<K> {
+    /// Checks if the delta holds no changes.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty() && self.links.is_empty()
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Returns a point from which [`delta_since`](Self::delta_since) collects
+    /// changes, starting to [record events](Self::record_events) if needed.
+    ///
+    /// Clearing the events, or rolling back or clearing recorded unions,
+    /// invalidates it.
+    pub fn sync_point(&mut self) -> SyncPoint {
+        let events = self.forest.events.get_or_insert_with(Vec::new).len();
+        SyncPoint { events, epoch: self.forest.log_epoch }
+    }
+
+    /// Applies a delta received from a replica. Only the keys and unions new
+    /// to this structure are recorded as events, so changes applied from one
+    /// peer can be forwarded to others without echoing.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let (mut east, mut west) = (DisjointHashSet::new(), DisjointHashSet::new());
+    /// let (east_sync, west_sync) = (east.sync_point(), west.sync_point());
+    ///
+    /// east.link("a", "b");
+    /// west.link("b", "c");
+    /// west.insert("d");
+    ///
+    /// let (from_east, from_west) = (east.delta_since(east_sync), west.delta_since(west_sync));
+    /// east.apply_delta(from_west.clone());
+    /// west.apply_delta(from_east);
+    /// west.apply_delta(from_west);
+    ///
+    /// assert!(east.is_linked("a", "c") && west.is_linked("a", "c"));
+    /// assert!(east.contains("d"));
+    /// assert_eq!(east.set_count(), west.set_count());
+    /// ```
+    pub fn apply_delta(&mut self, delta: Delta<K>) {
+        for key in delta.keys {
+            self.insert(key);
+        }
+        for (key1, key2) in delta.links {
+            self.link(key1, key2);
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Collects the keys inserted and the unions performed since the sync
+    /// point. The delta holds one link per union, rather than every link
+    /// passed to `link`, and omits inserted keys that appear in a link.
+    ///
+    /// # Panics
+    /// Panics if the sync point is no longer valid.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{Delta, DisjointHashSet};
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.link("a", "b");
+    ///
+    /// let sync = djhs.sync_point();
+    /// djhs.link("b", "c");
+    /// djhs.link("c", "a");
+    /// djhs.insert("d");
+    /// assert_eq!(djhs.delta_since(sync), Delta { keys: vec!["d"], links: vec![("b", "c")] });
+    /// ```
+    ///
+    /// Rolling back unions that were recorded invalidates the sync point:
+    ///
+    /// ```should_panic
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::new();
+    /// djhs.record_events();
+    /// let checkpoint = djhs.checkpoint();
+    /// djhs.link("a", "b");
+    /// let sync = djhs.sync_point();
+    /// djhs.rollback_to(checkpoint);
+    /// djhs.delta_since(sync);
+    /// ```
+    pub fn delta_since(&self, point: SyncPoint) -> Delta<K> {
+        let events = self.forest.events.as_deref().unwrap_or_default();
+        let valid = point.epoch == self.forest.log_epoch && point.events <= events.len();
+        assert!(valid, "sync point is no longer valid");
+
+        let (mut links, mut linked) = (Vec::new(), HashSet::new());
+        for event in &events[point.events..] {
+            if let LoggedEvent::Link(id1, id2) = *event {
+                links.push((self.key(id1).clone(), self.key(id2).clone()));
+                linked.extend([id1, id2]);
+            }
+        }
+
+        let keys = events[point.events..]
+            .iter()
+            .filter_map(|event| match *event {
+                LoggedEvent::Insert(id) if !linked.contains(&id) => Some(self.key(id).clone()),
+                _ => None,
+            })
+            .collect();
+        Delta { keys, links }
+    }
+}
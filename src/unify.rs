@@ -0,0 +1,189 @@
+use std::{collections::HashMap, error::Error, fmt, hash::Hash};
+
+use crate::{DisjointHashSet, PointerId};
+
+/// A term of a [`Unifier`]: a variable, or a constructor applied to terms,
+/// such as `List<a>` or `a -> b` for types.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Term<V, F> {
+    /// A variable.
+    Var(V),
+    /// A constructor applied to arguments, a constant if there are none.
+    App(F, Vec<Term<V, F>>),
+}
+
+/// A failure to unify two terms, as returned by [`Unifier::unify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnifyError<V, F> {
+    /// The terms apply different constructors, or the same constructor to a
+    /// different number of arguments.
+    Mismatch(Term<V, F>, Term<V, F>),
+    /// The variable would be bound to a term containing itself.
+    Occurs(V, Term<V, F>),
+}
+
+impl<V, F> fmt::Display for UnifyError<V, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnifyError::Mismatch(..) => f.write_str("terms have different constructors"),
+            UnifyError::Occurs(..) => f.write_str("variable occurs in the term it is bound to"),
+        }
+    }
+}
+
+impl<V: fmt::Debug, F: fmt::Debug> Error for UnifyError<V, F> {}
+
+/// Unification of terms with variables, as in Hindley–Milner type inference.
+///
+/// Variables unified with each other are linked in a `DisjointHashSet`, and
+/// every set of variables is bound to at most one term. Binding a variable to
+/// a term containing it fails the occurs check.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{Term, Unifier};
+///
+/// let var = |v| Term::Var(v);
+/// let list = |t| Term::App("List", vec![t]);
+/// let int = Term::App("Int", vec![]);
+///
+/// let mut unifier = Unifier::new();
+/// unifier.unify(&list(var("a")), &var("b")).unwrap();
+/// unifier.unify(&var("b"), &list(int.clone())).unwrap();
+/// assert_eq!(unifier.resolve(&var("a")), int);
+///
+/// // A list of `c` can't be `c` itself.
+/// assert!(unifier.unify(&var("c"), &list(var("c"))).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Unifier<V, F> {
+    vars: DisjointHashSet<V>,
+    /// The term of every set of variables that is bound, by root.
+    bindings: HashMap<PointerId, Term<V, F>>,
+}
+
+impl<V: Eq + Hash + Clone, F: Eq + Clone> Unifier<V, F> {
+    /// Creates a `Unifier` without variables.
+    pub fn new() -> Self {
+        Self { vars: DisjointHashSet::new(), bindings: HashMap::new() }
+    }
+
+    /// Unifies the two terms, linking and binding variables so that both
+    /// resolve to the same term.
+    ///
+    /// # Errors
+    /// Fails if the terms apply different constructors somewhere, or if a
+    /// variable would be bound to a term containing it. Unifications of the
+    /// subterms before the failure are kept, so the unifier should be cloned
+    /// beforehand to try a unification that may fail.
+    pub fn unify(
+        &mut self,
+        term1: &Term<V, F>,
+        term2: &Term<V, F>,
+    ) -> Result<(), UnifyError<V, F>> {
+        match (self.shallow_resolve(term1), self.shallow_resolve(term2)) {
+            (Term::Var(var1), Term::Var(var2)) => {
+                // Both sets are unbound, so joining them binds nothing.
+                let ids = (self.vars.id_or_insert(var1), self.vars.id_or_insert(var2));
+                self.vars.union(ids.0, ids.1);
+                Ok(())
+            }
+            (Term::Var(var), term) | (term, Term::Var(var)) => {
+                let id = self.vars.id_or_insert(var);
+                let root = self.vars.find(id);
+                self.bind(root, term)
+            }
+            (Term::App(symbol1, args1), Term::App(symbol2, args2)) => {
+                if symbol1 != symbol2 || args1.len() != args2.len() {
+                    return Err(UnifyError::Mismatch(
+                        Term::App(symbol1, args1),
+                        Term::App(symbol2, args2),
+                    ));
+                }
+                args1.iter().zip(&args2).try_for_each(|(arg1, arg2)| self.unify(arg1, arg2))
+            }
+        }
+    }
+
+    /// Applies the substitution to the term, replacing every bound variable
+    /// by its term and every other variable by the representative of its set.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{Term, Unifier};
+    ///
+    /// let mut unifier = Unifier::<_, &str>::new();
+    /// unifier.unify(&Term::Var("a"), &Term::Var("b")).unwrap();
+    /// assert_eq!(unifier.resolve(&Term::Var("b")), unifier.resolve(&Term::Var("a")));
+    /// ```
+    pub fn resolve(&mut self, term: &Term<V, F>) -> Term<V, F> {
+        match self.shallow_resolve(term) {
+            Term::Var(var) => Term::Var(var),
+            Term::App(symbol, args) => {
+                Term::App(symbol, args.iter().map(|arg| self.resolve(arg)).collect())
+            }
+        }
+    }
+
+    /// Returns the final substitution: the resolved term of every variable
+    /// that is bound, or linked to a variable other than itself.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{Term, Unifier};
+    ///
+    /// let mut unifier = Unifier::new();
+    /// unifier.unify(&Term::Var("t"), &Term::App("Bool", vec![])).unwrap();
+    /// unifier.unify(&Term::Var("u"), &Term::Var("u")).unwrap();
+    ///
+    /// let substitution = unifier.substitution();
+    /// assert_eq!(substitution.len(), 1);
+    /// assert_eq!(substitution["t"], Term::App("Bool", vec![]));
+    /// ```
+    pub fn substitution(&mut self) -> HashMap<V, Term<V, F>> {
+        let vars: Vec<V> = self.vars.keys().cloned().collect();
+        vars.into_iter()
+            .filter_map(|var| {
+                let term = self.resolve(&Term::Var(var.clone()));
+                (term != Term::Var(var.clone())).then_some((var, term))
+            })
+            .collect()
+    }
+
+    /// Binds the unbound set of variables of the root to the term.
+    fn bind(&mut self, root: PointerId, term: Term<V, F>) -> Result<(), UnifyError<V, F>> {
+        if self.occurs(root, &term) {
+            return Err(UnifyError::Occurs(self.vars.key(root).clone(), self.resolve(&term)));
+        }
+        self.bindings.insert(root, term);
+        Ok(())
+    }
+
+    /// Checks if a variable of the set of the root occurs in the term.
+    fn occurs(&mut self, root: PointerId, term: &Term<V, F>) -> bool {
+        match self.shallow_resolve(term) {
+            Term::Var(var) => self.vars.id(&var).is_some_and(|id| self.vars.find(id) == root),
+            Term::App(_, args) => args.iter().any(|arg| self.occurs(root, arg)),
+        }
+    }
+
+    /// Follows the bindings of a variable until reaching a constructor or an
+    /// unbound variable, which is replaced by the representative of its set.
+    fn shallow_resolve(&mut self, term: &Term<V, F>) -> Term<V, F> {
+        let Term::Var(var) = term else {
+            return term.clone();
+        };
+        let Some(id) = self.vars.id(var) else {
+            return term.clone();
+        };
+        let root = self.vars.find(id);
+        match self.bindings.get(&root) {
+            Some(bound) => bound.clone(),
+            None => Term::Var(self.vars.key(root).clone()),
+        }
+    }
+}
+
+impl<V: Eq + Hash + Clone, F: Eq + Clone> Default for Unifier<V, F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,365 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+use crate::PointerId;
+
+/// A `DisjointHashSet` that can answer connectivity queries for any past
+/// version of the partition.
+///
+/// Every effective `link` advances the version by one, starting from `0` for
+/// the partition in which every key is a singleton. This is a
+/// [`TimestampedDisjointHashSet`] whose timestamps are the versions.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::VersionedDisjointHashSet;
+///
+/// let mut djhs = VersionedDisjointHashSet::new();
+/// djhs.link("a", "b");
+/// let before = djhs.version();
+/// djhs.link("b", "c");
+///
+/// assert!(djhs.is_linked("a", "c"));
+/// assert!(!djhs.is_linked_at("a", "c", before));
+/// assert!(djhs.is_linked_at("a", "b", before));
+/// ```
+#[derive(Debug, Clone)]
+pub struct VersionedDisjointHashSet<K, S = RandomState> {
+    inner: TimestampedDisjointHashSet<K, u64, S>,
+    version: u64,
+}
+
+impl<K: Eq + Hash> VersionedDisjointHashSet<K, RandomState> {
+    /// Creates an empty `VersionedDisjointHashSet`.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::VersionedDisjointHashSet;
+    /// let mut djhs: VersionedDisjointHashSet<&str> = VersionedDisjointHashSet::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Eq + Hash> Default for VersionedDisjointHashSet<K, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> VersionedDisjointHashSet<K, S> {
+    /// Creates an empty `VersionedDisjointHashSet` which will use the given
+    /// hash builder to hash keys.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::hash::RandomState;
+    /// use disjoint_hash_set::VersionedDisjointHashSet;
+    /// let s = RandomState::new();
+    /// let mut djhs: VersionedDisjointHashSet<&str> = VersionedDisjointHashSet::with_hasher(s);
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self { inner: TimestampedDisjointHashSet::with_hasher(hash_builder), version: 0 }
+    }
+
+    /// The current version, i.e. the number of effective unions so far.
+    ///
+    /// ```
+    /// use disjoint_hash_set::VersionedDisjointHashSet;
+    ///
+    /// let mut djhs = VersionedDisjointHashSet::new();
+    /// assert_eq!(djhs.version(), 0);
+    /// djhs.link("a", "b");
+    /// djhs.link("b", "a");
+    /// assert_eq!(djhs.version(), 1);
+    /// ```
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Check if the value has already been inserted.
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.inner.contains(val)
+    }
+
+    /// Insert the value as a new disjoint set with a single member. Returns
+    /// true if the value was not already present. Insertions do not advance
+    /// the version.
+    pub fn insert(&mut self, val: K) -> bool {
+        self.inner.insert(val)
+    }
+
+    /// Checks if the two keys are members of the same set in the current
+    /// version. This will not implicitly add values that were not already
+    /// present.
+    pub fn is_linked<T: Borrow<K>>(&self, val1: T, val2: T) -> bool {
+        self.inner.is_linked(val1, val2)
+    }
+
+    /// Checks if the two keys were members of the same set as of `version`.
+    /// Keys inserted after `version` are treated as having been singletons.
+    ///
+    /// ```
+    /// use disjoint_hash_set::VersionedDisjointHashSet;
+    ///
+    /// let mut djhs = VersionedDisjointHashSet::new();
+    /// djhs.link(1, 2); // version 1
+    /// djhs.link(3, 4); // version 2
+    /// djhs.link(2, 3); // version 3
+    ///
+    /// assert!(!djhs.is_linked_at(1, 2, 0));
+    /// assert!(djhs.is_linked_at(1, 2, 1));
+    /// assert!(!djhs.is_linked_at(1, 4, 2));
+    /// assert!(djhs.is_linked_at(1, 4, 3));
+    /// ```
+    pub fn is_linked_at<T: Borrow<K>>(&self, val1: T, val2: T, version: u64) -> bool {
+        self.inner.is_linked_at(val1, val2, version)
+    }
+
+    /// Returns the version at which the two keys first became linked, or
+    /// `None` if they are not linked or are the same key.
+    ///
+    /// ```
+    /// use disjoint_hash_set::VersionedDisjointHashSet;
+    ///
+    /// let mut djhs = VersionedDisjointHashSet::new();
+    /// djhs.link(1, 2); // version 1
+    /// djhs.link(2, 3); // version 2
+    /// assert_eq!(djhs.earliest_link_version(1, 2), Some(1));
+    /// assert_eq!(djhs.earliest_link_version(1, 3), Some(2));
+    /// ```
+    pub fn earliest_link_version<T: Borrow<K>>(&self, val1: T, val2: T) -> Option<u64> {
+        self.inner.earliest_link_time(val1, val2)
+    }
+
+    /// Link the respective sets of the two provided values, advancing the
+    /// version if they were not already linked. This will insert non-existent
+    /// values in the process.
+    ///
+    /// ```
+    /// use disjoint_hash_set::VersionedDisjointHashSet;
+    ///
+    /// let mut djhs = VersionedDisjointHashSet::new();
+    /// djhs.link("a", "a");
+    /// assert!(djhs.contains("a"));
+    /// assert_eq!(djhs.version(), 0);
+    /// ```
+    pub fn link(&mut self, val1: K, val2: K) {
+        if self.inner.link_at(val1, val2, self.version + 1) {
+            self.version += 1;
+        }
+    }
+}
+
+/// A `DisjointHashSet` whose unions carry a timestamp, answering when two keys
+/// first became connected and whether they were connected at a past time.
+///
+/// Each parent pointer remembers the timestamp of the union that set it and,
+/// as path compression is not applied, following only the pointers set at or
+/// before a time recovers the partition as it was at that time. The time two
+/// keys became connected is the latest timestamp on the path between them in
+/// this forest. Rank-based joins bound queries to `O(log n)`, and no snapshots
+/// are stored.
+///
+/// Timestamps can be any `Ord` type, e.g. instants or edge weights, but must
+/// be supplied in non-decreasing order.
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::TimestampedDisjointHashSet;
+///
+/// let mut djhs = TimestampedDisjointHashSet::new();
+/// djhs.link_at("alice", "card-1", 100);
+/// djhs.link_at("bob", "card-2", 150);
+/// djhs.link_at("card-1", "device", 200);
+/// djhs.link_at("device", "card-2", 250);
+///
+/// assert_eq!(djhs.earliest_link_time("alice", "device"), Some(200));
+/// assert_eq!(djhs.earliest_link_time("alice", "bob"), Some(250));
+/// assert!(!djhs.is_linked_at("alice", "bob", 249));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimestampedDisjointHashSet<K, T, S = RandomState> {
+    ids: HashMap<K, PointerId, S>,
+    data: Vec<TimestampedPointer<T>>,
+    latest: Option<T>,
+}
+
+impl<K: Eq + Hash, T: Ord + Copy> TimestampedDisjointHashSet<K, T, RandomState> {
+    /// Creates an empty `TimestampedDisjointHashSet`.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::TimestampedDisjointHashSet;
+    /// let mut djhs: TimestampedDisjointHashSet<&str, u64> = TimestampedDisjointHashSet::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Eq + Hash, T: Ord + Copy> Default for TimestampedDisjointHashSet<K, T, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, T: Ord + Copy, S: BuildHasher> TimestampedDisjointHashSet<K, T, S> {
+    /// Creates an empty `TimestampedDisjointHashSet` which will use the given
+    /// hash builder to hash keys.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::hash::RandomState;
+    /// use disjoint_hash_set::TimestampedDisjointHashSet;
+    /// let s = RandomState::new();
+    /// let mut djhs: TimestampedDisjointHashSet<&str, u64> =
+    ///     TimestampedDisjointHashSet::with_hasher(s);
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self { ids: HashMap::with_hasher(hash_builder), data: Vec::new(), latest: None }
+    }
+
+    /// The timestamp of the most recent `link_at`, if any.
+    pub fn latest(&self) -> Option<T> {
+        self.latest
+    }
+
+    /// Check if the value has already been inserted.
+    pub fn contains<Q: Borrow<K>>(&self, val: Q) -> bool {
+        self.ids.contains_key(val.borrow())
+    }
+
+    /// Insert the value as a new disjoint set with a single member. Returns
+    /// true if the value was not already present.
+    pub fn insert(&mut self, val: K) -> bool {
+        (!self.contains(&val)).then(|| self.insert_unchecked(val)).is_some()
+    }
+
+    /// Checks if the two keys are currently members of the same set.
+    /// This will not implicitly add values that were not already present.
+    pub fn is_linked<Q: Borrow<K>>(&self, val1: Q, val2: Q) -> bool {
+        match (self.ids.get(val1.borrow()), self.ids.get(val2.borrow())) {
+            (Some(&id1), Some(&id2)) => self.root_at(id1, None) == self.root_at(id2, None),
+            _ => false,
+        }
+    }
+
+    /// Checks if the two keys were members of the same set as of `time`, i.e.
+    /// considering only unions with a timestamp no later than `time`. Keys
+    /// inserted later are treated as having been singletons.
+    ///
+    /// ```
+    /// use disjoint_hash_set::TimestampedDisjointHashSet;
+    ///
+    /// let mut djhs = TimestampedDisjointHashSet::new();
+    /// djhs.link_at("a", "b", 10);
+    /// assert!(!djhs.is_linked_at("a", "b", 9));
+    /// assert!(djhs.is_linked_at("a", "b", 10));
+    /// ```
+    pub fn is_linked_at<Q: Borrow<K>>(&self, val1: Q, val2: Q, time: T) -> bool {
+        match (self.ids.get(val1.borrow()), self.ids.get(val2.borrow())) {
+            (Some(&id1), Some(&id2)) => {
+                self.root_at(id1, Some(time)) == self.root_at(id2, Some(time))
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the timestamp at which the two keys first became linked, or
+    /// `None` if they are not linked or are the same key.
+    ///
+    /// ```
+    /// use disjoint_hash_set::TimestampedDisjointHashSet;
+    ///
+    /// let mut djhs = TimestampedDisjointHashSet::new();
+    /// djhs.link_at("a", "b", 3);
+    /// djhs.insert("c");
+    /// assert_eq!(djhs.earliest_link_time("b", "a"), Some(3));
+    /// assert_eq!(djhs.earliest_link_time("a", "c"), None);
+    /// assert_eq!(djhs.earliest_link_time("a", "a"), None);
+    /// ```
+    pub fn earliest_link_time<Q: Borrow<K>>(&self, val1: Q, val2: Q) -> Option<T> {
+        let (mut id1, mut id2) = (*self.ids.get(val1.borrow())?, *self.ids.get(val2.borrow())?);
+
+        // Latest timestamp seen on the way up from `id1` to each ancestor.
+        let mut ancestors = vec![(id1, None)];
+        while self.data[id1.0].parent != id1 {
+            let latest = ancestors.last().unwrap().1.max(self.data[id1.0].time);
+            id1 = self.data[id1.0].parent;
+            ancestors.push((id1, latest));
+        }
+
+        let mut latest = None;
+        loop {
+            if let Some((_, time)) = ancestors.iter().find(|(id, _)| *id == id2) {
+                return latest.max(*time);
+            }
+            if self.data[id2.0].parent == id2 {
+                return None;
+            }
+            latest = latest.max(self.data[id2.0].time);
+            id2 = self.data[id2.0].parent;
+        }
+    }
+
+    /// Link the respective sets of the two provided values at `time`. This
+    /// will insert non-existent values in the process. Returns true if the
+    /// sets were disjoint.
+    ///
+    /// # Panics
+    /// Panics if `time` is earlier than the timestamp of a previous link.
+    pub fn link_at(&mut self, val1: K, val2: K, time: T) -> bool {
+        assert!(self.latest <= Some(time), "timestamps must be non-decreasing");
+        self.latest = Some(time);
+
+        let ids = (self.id_or_insert(val1), self.id_or_insert(val2));
+        let roots = (self.root_at(ids.0, None), self.root_at(ids.1, None));
+
+        if roots.0 != roots.1 {
+            let ranks = (self.data[roots.0.0].rank, self.data[roots.1.0].rank);
+            let (root, child) =
+                if ranks.0 < ranks.1 { (roots.1, roots.0) } else { (roots.0, roots.1) };
+
+            self.data[child.0].parent = root;
+            self.data[child.0].time = Some(time);
+            if ranks.0 == ranks.1 {
+                self.data[root.0].rank += 1;
+            }
+        }
+        roots.0 != roots.1
+    }
+
+    /// Finds the root of the id as of `time`, ignoring later unions, or the
+    /// current root if `time` is `None`.
+    fn root_at(&self, mut id: PointerId, time: Option<T>) -> PointerId {
+        while self.data[id.0].parent != id && (time.is_none() || self.data[id.0].time <= time) {
+            id = self.data[id.0].parent;
+        }
+        id
+    }
+
+    fn id_or_insert(&mut self, value: K) -> PointerId {
+        self.ids.get(&value).copied().unwrap_or_else(|| self.insert_unchecked(value))
+    }
+
+    fn insert_unchecked(&mut self, value: K) -> PointerId {
+        let id = PointerId(self.data.len());
+        self.ids.insert(value, id);
+        self.data.push(TimestampedPointer { parent: id, rank: 0, time: None });
+        id
+    }
+}
+
+/// A parent pointer along with the timestamp of the union that set it, which
+/// is `None` for roots.
+#[derive(Debug, Clone)]
+struct TimestampedPointer<T> {
+    parent: PointerId,
+    rank: u8,
+    time: Option<T>,
+}
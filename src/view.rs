@@ -0,0 +1,97 @@
+use std::{
+    hash::{BuildHasher, Hash},
+    iter::FusedIterator,
+};
+
+use crate::{DisjointHashSet, PointerId};
+
+/// The members of every set, grouped once and cached by a `DisjointHashSet`
+/// until its next change.
+#[derive(Debug, Clone)]
+pub(crate) struct Components {
+    /// The ids of the members of every set, set after set.
+    members: Vec<PointerId>,
+    /// The index into `members` of the first member of every set.
+    starts: Vec<usize>,
+}
+
+/// The sets of a `DisjointHashSet`, as returned by
+/// [`DisjointHashSet::components_view`].
+///
+/// Sets are ordered by their first keys, and their members by insertion.
+#[derive(Debug)]
+pub struct ComponentsView<'a, K> {
+    keys: &'a [K],
+    components: &'a Components,
+}
+
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Returns a view of the sets, grouping the keys on first access and
+    /// reusing the grouping until the next insertion or effective union, so
+    /// that repeated calls between changes cost nothing.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "c")]);
+    /// let view = djhs.components_view();
+    /// assert_eq!(view.len(), 2);
+    /// assert_eq!(view.get(0).unwrap().collect::<Vec<_>>(), vec![&"a", &"b"]);
+    ///
+    /// djhs.link("b", "c");
+    /// assert_eq!(djhs.components_view().len(), 1);
+    /// ```
+    pub fn components_view(&self) -> ComponentsView<'_, K> {
+        let components = self.forest.components.get_or_init(|| {
+            let (mut members, mut starts) = (Vec::with_capacity(self.forest.len()), Vec::new());
+            for set in self.grouped_ids() {
+                starts.push(members.len());
+                members.extend(set);
+            }
+            Components { members, starts }
+        });
+        ComponentsView { keys: &self.keys, components }
+    }
+}
+
+impl<K> Clone for ComponentsView<'_, K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K> Copy for ComponentsView<'_, K> {}
+
+impl<'a, K> ComponentsView<'a, K> {
+    /// The number of sets.
+    pub fn len(&self) -> usize {
+        self.components.starts.len()
+    }
+
+    /// Checks if there are no sets.
+    pub fn is_empty(&self) -> bool {
+        self.components.starts.is_empty()
+    }
+
+    /// Returns an iterator over the members of the set at the index, or `None`
+    /// if it is out of range.
+    pub fn get(
+        &self,
+        index: usize,
+    ) -> Option<impl ExactSizeIterator<Item = &'a K> + FusedIterator> {
+        let Components { members, starts } = self.components;
+        let start = *starts.get(index)?;
+        let end = starts.get(index + 1).copied().unwrap_or(members.len());
+        let keys = self.keys;
+        Some(members[start..end].iter().map(move |id| &keys[id.0]))
+    }
+
+    /// Returns an iterator over the sets, each an iterator over its members.
+    pub fn iter(
+        &self,
+    ) -> impl ExactSizeIterator<Item = impl ExactSizeIterator<Item = &'a K> + FusedIterator>
+    + FusedIterator {
+        let view = *self;
+        (0..self.len()).map(move |index| view.get(index).expect("index is in range"))
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    borrow::Borrow,
+    hash::{BuildHasher, Hash, RandomState},
+    io::{self, Read, Write},
+};
+
+use crate::{BinaryKey, DisjointHashSet};
+
+/// The tag of an insert record, followed by the key.
+const INSERT: u8 = 0;
+/// The tag of a link record, followed by the two keys.
+const LINK: u8 = 1;
+
+/// A `DisjointHashSet` appending every change to a write-ahead log, from
+/// which [`DisjointHashSet::recover`] rebuilds the partition after a crash.
+///
+/// A record is written before the change is applied, as a single write of a
+/// tag byte followed by the [binary keys](BinaryKey). Inserts of present keys
+/// and links of keys already linked change nothing and are not logged. How
+/// much of the log survives a crash depends on the writer: a `File` keeps
+/// every written record once the process dies, while a `BufWriter` first has
+/// to be [flushed](Self::flush).
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::{DisjointHashSet, WalDisjointHashSet};
+///
+/// let mut djhs = WalDisjointHashSet::new(Vec::new());
+/// djhs.link(1u32, 2).unwrap();
+/// djhs.insert(3).unwrap();
+/// djhs.link(2, 1).unwrap(); // not logged
+/// let log = djhs.into_writer();
+///
+/// let mut recovered = DisjointHashSet::<u32>::recover(log.as_slice()).unwrap();
+/// assert!(recovered.is_linked(1, 2));
+/// assert_eq!(recovered.set_count(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WalDisjointHashSet<K, W, S = RandomState> {
+    set: DisjointHashSet<K, S>,
+    writer: W,
+}
+
+impl<K: Eq + Hash + BinaryKey, W: Write> WalDisjointHashSet<K, W, RandomState> {
+    /// Creates an empty `WalDisjointHashSet` logging to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self::resume(DisjointHashSet::new(), writer)
+    }
+}
+
+impl<K: Eq + Hash + BinaryKey, W: Write, S: BuildHasher> WalDisjointHashSet<K, W, S> {
+    /// Creates an empty `WalDisjointHashSet` logging to `writer`, using
+    /// `hash_builder` to hash the keys.
+    pub fn with_hasher(writer: W, hash_builder: S) -> Self {
+        Self::resume(DisjointHashSet::with_hasher(hash_builder), writer)
+    }
+
+    /// Continues logging the changes to `set`, such as one returned by
+    /// [`DisjointHashSet::recover`], to `writer`. The keys already in `set`
+    /// are not written, so `writer` should append to the log `set` was
+    /// recovered from.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{DisjointHashSet, WalDisjointHashSet};
+    ///
+    /// let mut djhs = WalDisjointHashSet::new(Vec::new());
+    /// djhs.link("a".to_string(), "b".to_string()).unwrap();
+    /// let log = djhs.into_writer();
+    ///
+    /// let recovered = DisjointHashSet::recover(log.as_slice()).unwrap();
+    /// let mut djhs = WalDisjointHashSet::resume(recovered, log);
+    /// djhs.link("b".to_string(), "c".to_string()).unwrap();
+    ///
+    /// let log = djhs.into_writer();
+    /// let mut recovered = DisjointHashSet::<String>::recover(log.as_slice()).unwrap();
+    /// assert!(recovered.is_linked("a".to_string(), "c".to_string()));
+    /// ```
+    pub fn resume(set: DisjointHashSet<K, S>, writer: W) -> Self {
+        Self { set, writer }
+    }
+
+    /// Check if the value has already been inserted.
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.set.contains(val)
+    }
+
+    /// Insert the value as a new disjoint set with a single member, logging
+    /// it first. Returns true if the value was not already present.
+    ///
+    /// # Errors
+    /// Fails if writing to the log fails, leaving the partition unchanged.
+    pub fn insert(&mut self, val: K) -> io::Result<bool> {
+        if self.set.contains(&val) {
+            return Ok(false);
+        }
+
+        let mut record = vec![INSERT];
+        val.write_key(&mut record)?;
+        self.writer.write_all(&record)?;
+        Ok(self.set.insert(val))
+    }
+
+    /// Checks if the two keys are members of the same set.
+    /// This will not implicitly add values that were not already present.
+    pub fn is_linked<T: Borrow<K>>(&mut self, val1: T, val2: T) -> bool {
+        self.set.is_linked(val1, val2)
+    }
+
+    /// Link the respective sets of the two provided values, logging the link
+    /// first if they were not already linked. This will insert non-existent
+    /// values in the process.
+    ///
+    /// # Errors
+    /// Fails if writing to the log fails, leaving the partition unchanged.
+    pub fn link(&mut self, val1: K, val2: K) -> io::Result<()> {
+        if self.set.is_linked(&val1, &val2) {
+            return Ok(());
+        }
+
+        let mut record = vec![LINK];
+        val1.write_key(&mut record)?;
+        val2.write_key(&mut record)?;
+        self.writer.write_all(&record)?;
+        self.set.link(val1, val2);
+        Ok(())
+    }
+
+    /// Flushes the log.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns a reference to the underlying `DisjointHashSet`.
+    pub fn get_ref(&self) -> &DisjointHashSet<K, S> {
+        &self.set
+    }
+
+    /// Consumes the structure, returning the log.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    /// Consumes the structure, returning the underlying `DisjointHashSet` and
+    /// the log.
+    pub fn into_parts(self) -> (DisjointHashSet<K, S>, W) {
+        (self.set, self.writer)
+    }
+}
+
+impl<K: Eq + Hash + BinaryKey> DisjointHashSet<K, RandomState> {
+    /// Rebuilds a `DisjointHashSet` by replaying a log written by a
+    /// [`WalDisjointHashSet`]. A record cut short at the end of the log, as
+    /// left by a crash in the middle of a write, is ignored.
+    ///
+    /// # Errors
+    /// Fails if reading fails, or with `InvalidData` if a record is neither
+    /// an insert nor a link, or holds an invalid key.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{DisjointHashSet, WalDisjointHashSet};
+    ///
+    /// let mut djhs = WalDisjointHashSet::new(Vec::new());
+    /// djhs.link(1u64, 2).unwrap();
+    /// djhs.link(2, 3).unwrap();
+    /// let mut log = djhs.into_writer();
+    /// log.truncate(log.len() - 4);
+    ///
+    /// let mut recovered = DisjointHashSet::<u64>::recover(log.as_slice()).unwrap();
+    /// assert!(recovered.is_linked(1, 2));
+    /// assert!(!recovered.contains(3));
+    /// ```
+    pub fn recover<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut djhs = Self::new();
+        loop {
+            let mut tag = [0];
+            match reader.read(&mut tag) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            }
+
+            let applied = match tag[0] {
+                INSERT => K::read_key(&mut reader).map(|key| {
+                    djhs.insert(key);
+                }),
+                LINK => K::read_key(&mut reader)
+                    .and_then(|key1| Ok((key1, K::read_key(&mut reader)?)))
+                    .map(|(key1, key2)| djhs.link(key1, key2)),
+                tag => {
+                    let message = format!("unknown log record {tag}");
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+                }
+            };
+            match applied {
+                Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+                applied => applied?,
+            }
+        }
+        Ok(djhs)
+    }
+}
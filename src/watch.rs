@@ -0,0 +1,44 @@
+use std::{
+    borrow::Borrow,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{DisjointHashSet, PointerId};
+
+/// A token for the set of a key, as returned by [`DisjointHashSet::watch`],
+/// that tells whether the set has changed since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WatchToken {
+    id: PointerId,
+    size: usize,
+    epoch: u64,
+}
+
+impl<K: Eq + Hash, S: BuildHasher> DisjointHashSet<K, S> {
+    /// Returns a token for the current set of the value, or `None` if the value
+    /// is not present.
+    ///
+    /// ```
+    /// use disjoint_hash_set::DisjointHashSet;
+    ///
+    /// let mut djhs = DisjointHashSet::from_iter(vec![("a", "b"), ("c", "d")]);
+    /// let token = djhs.watch("a").unwrap();
+    ///
+    /// djhs.link("c", "e");
+    /// assert!(!djhs.has_changed(token));
+    /// djhs.link("b", "c");
+    /// assert!(djhs.has_changed(token));
+    /// ```
+    pub fn watch<T: Borrow<K>>(&self, val: T) -> Option<WatchToken> {
+        let id = self.id(val.borrow())?;
+        Some(WatchToken { id, size: self.get(self.root(id)).size, epoch: self.forest.epoch })
+    }
+
+    /// Checks if the set of the watched key has changed since the token was
+    /// taken. Sets only change by growing, so this compares the size of the
+    /// set, except that a rollback, cleared links or released checkpoints
+    /// conservatively count as a change to every set.
+    pub fn has_changed(&self, token: WatchToken) -> bool {
+        token.epoch != self.forest.epoch || self.get(self.root(token.id)).size != token.size
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    error::Error,
+    fmt,
+    hash::{BuildHasher, Hash, RandomState},
+    iter::FusedIterator,
+};
+
+use crate::PointerId;
+
+/// A commutative group of relations between keys, such as differences of
+/// integers, or products of exchange rates.
+///
+/// Implemented for the signed integers, with addition, and for `bool`, with
+/// exclusive or.
+pub trait Group: Clone + PartialEq {
+    /// The relation of every key to itself.
+    fn identity() -> Self;
+
+    /// Composes two relations.
+    fn combine(&self, other: &Self) -> Self;
+
+    /// The relation in the opposite direction.
+    fn inverse(&self) -> Self;
+}
+
+macro_rules! impl_additive_group {
+    ($($t:ty),*) => {
+        $(impl Group for $t {
+            fn identity() -> Self {
+                0
+            }
+
+            fn combine(&self, other: &Self) -> Self {
+                self + other
+            }
+
+            fn inverse(&self) -> Self {
+                -self
+            }
+        })*
+    };
+}
+
+impl_additive_group!(i8, i16, i32, i64, i128, isize);
+
+impl Group for bool {
+    fn identity() -> Self {
+        false
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        self ^ other
+    }
+
+    fn inverse(&self) -> Self {
+        *self
+    }
+}
+
+/// A `DisjointHashSet` whose links carry a relation between their keys in a
+/// [`Group`], e.g. `a - b = w`.
+///
+/// Every key stores its relation to its parent, so the relation between any
+/// two linked keys can be derived, and links inconsistent with a known
+/// relation fail with a [`Contradiction`].
+///
+/// # Example
+/// ```
+/// use disjoint_hash_set::WeightedDisjointHashSet;
+///
+/// // Heights relative to one another, in cm.
+/// let mut djhs = WeightedDisjointHashSet::new();
+/// djhs.link("alice", "bob", 5).unwrap();
+/// djhs.link("carol", "bob", -3).unwrap();
+///
+/// assert_eq!(djhs.relation("alice", "carol"), Some(8));
+/// assert_eq!(djhs.relation("carol", "alice"), Some(-8));
+/// assert!(djhs.link("alice", "carol", 7).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeightedDisjointHashSet<K, G, S = RandomState> {
+    ids: HashMap<K, PointerId, S>,
+    data: Vec<WeightedPointer<G>>,
+}
+
+impl<K: Eq + Hash, G: Group> WeightedDisjointHashSet<K, G, RandomState> {
+    /// Creates an empty `WeightedDisjointHashSet`.
+    ///
+    /// # Example
+    /// ```
+    /// use disjoint_hash_set::WeightedDisjointHashSet;
+    /// let mut djhs: WeightedDisjointHashSet<&str, i64> = WeightedDisjointHashSet::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Eq + Hash, G: Group> Default for WeightedDisjointHashSet<K, G, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, G: Group, S: BuildHasher> WeightedDisjointHashSet<K, G, S> {
+    /// Creates an empty `WeightedDisjointHashSet` which will use the given
+    /// hash builder to hash keys.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::hash::RandomState;
+    /// use disjoint_hash_set::WeightedDisjointHashSet;
+    /// let s = RandomState::new();
+    /// let mut djhs: WeightedDisjointHashSet<&str, i64> = WeightedDisjointHashSet::with_hasher(s);
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self { ids: HashMap::with_hasher(hash_builder), data: Vec::new() }
+    }
+
+    /// Check if the value has already been inserted.
+    pub fn contains<T: Borrow<K>>(&self, val: T) -> bool {
+        self.ids.contains_key(val.borrow())
+    }
+
+    /// Insert the value as a new disjoint set with a single member. Returns
+    /// true if the value was not already present.
+    pub fn insert(&mut self, val: K) -> bool {
+        (!self.contains(&val)).then(|| self.insert_unchecked(val)).is_some()
+    }
+
+    /// Checks if the two keys are members of the same set, i.e. if their
+    /// relation is known. This will not implicitly add values that were not
+    /// already present.
+    pub fn is_linked<T: Borrow<K>>(&mut self, val1: T, val2: T) -> bool {
+        self.relation(val1, val2).is_some()
+    }
+
+    /// Returns the relation `val1 - val2`, or `None` if it is unknown because
+    /// the keys are not linked.
+    pub fn relation<T: Borrow<K>>(&mut self, val1: T, val2: T) -> Option<G> {
+        let (id1, id2) = (*self.ids.get(val1.borrow())?, *self.ids.get(val2.borrow())?);
+        let ((root1, weight1), (root2, weight2)) = (self.find(id1), self.find(id2));
+        (root1 == root2).then(|| weight1.combine(&weight2.inverse()))
+    }
+
+    /// Links the two values with the relation `val1 - val2 = relation`. This
+    /// will insert non-existent values in the process. Fails, returning the
+    /// values, if a different relation between them is already known.
+    ///
+    /// ```
+    /// use disjoint_hash_set::{Contradiction, WeightedDisjointHashSet};
+    ///
+    /// let mut djhs = WeightedDisjointHashSet::new();
+    /// djhs.link("a", "b", 2).unwrap();
+    /// djhs.link("b", "c", 3).unwrap();
+    /// assert!(djhs.link("a", "c", 5).is_ok());
+    /// assert_eq!(djhs.link("c", "a", 5), Err(Contradiction("c", "a")));
+    /// ```
+    pub fn link(&mut self, val1: K, val2: K, relation: G) -> Result<(), Contradiction<K>> {
+        if self.relation(&val1, &val2).is_some_and(|known| known != relation) {
+            return Err(Contradiction(val1, val2));
+        }
+
+        let ids = (self.id_or_insert(val1), self.id_or_insert(val2));
+        let ((root1, weight1), (root2, weight2)) = (self.find(ids.0), self.find(ids.1));
+
+        if root1 != root2 {
+            // root1 - root2 = relation + weight2 - weight1
+            let between = relation.combine(&weight2).combine(&weight1.inverse());
+
+            let ranks = (self.data[root1.0].rank, self.data[root2.0].rank);
+            let (root, child, weight) = if ranks.0 < ranks.1 {
+                (root2, root1, between)
+            } else {
+                (root1, root2, between.inverse())
+            };
+
+            self.data[child.0].parent = root;
+            self.data[child.0].weight = weight;
+            if ranks.0 == ranks.1 {
+                self.data[root.0].rank += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Consumes the structure and returns each disjoint set as a map from its
+    /// members to their relation to a common, arbitrary member of the set.
+    ///
+    /// ```
+    /// use disjoint_hash_set::WeightedDisjointHashSet;
+    ///
+    /// let mut djhs = WeightedDisjointHashSet::new();
+    /// djhs.link("a", "b", 1).unwrap();
+    /// djhs.link("c", "b", 4).unwrap();
+    ///
+    /// let set = djhs.sets().next().unwrap();
+    /// assert_eq!(set["c"] - set["a"], 3);
+    /// ```
+    pub fn sets(mut self) -> impl ExactSizeIterator<Item = HashMap<K, G>> + FusedIterator {
+        let mut sets = HashMap::new();
+
+        let roots: Vec<(PointerId, G)> =
+            (0..self.data.len()).map(|id| self.find(PointerId(id))).collect();
+
+        self.ids.into_iter().for_each(|(val, id)| {
+            let (root, weight) = roots[id.0].clone();
+            sets.entry(root).or_insert_with(HashMap::new).insert(val, weight);
+        });
+
+        sets.into_values()
+    }
+
+    /// Finds the root of the id along with the relation of the id to the
+    /// root, compressing the path.
+    fn find(&mut self, id: PointerId) -> (PointerId, G) {
+        let parent = self.data[id.0].parent;
+        if parent == id {
+            return (id, G::identity());
+        }
+
+        let (root, parent_weight) = self.find(parent);
+        let weight = self.data[id.0].weight.combine(&parent_weight);
+        self.data[id.0].parent = root;
+        self.data[id.0].weight = weight.clone();
+        (root, weight)
+    }
+
+    fn id_or_insert(&mut self, value: K) -> PointerId {
+        self.ids.get(&value).copied().unwrap_or_else(|| self.insert_unchecked(value))
+    }
+
+    fn insert_unchecked(&mut self, value: K) -> PointerId {
+        let id = PointerId(self.data.len());
+        self.ids.insert(value, id);
+        self.data.push(WeightedPointer { parent: id, rank: 0, weight: G::identity() });
+        id
+    }
+}
+
+/// A parent pointer along with the relation of the key to its parent.
+#[derive(Debug, Clone)]
+struct WeightedPointer<G> {
+    parent: PointerId,
+    rank: u8,
+    weight: G,
+}
+
+/// The keys of a link contradicting their known relation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contradiction<K>(pub K, pub K);
+
+impl<K> fmt::Display for Contradiction<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("link contradicts the known relation between its keys")
+    }
+}
+
+impl<K: fmt::Debug> Error for Contradiction<K> {}